@@ -41,12 +41,16 @@ impl<F: utils::Float, const NUM_BANDS: usize> Settings<F, NUM_BANDS> {
             eq::Eq {
                 gain: eq::Gain::Db(F::from(3).unwrap()),
                 frequency: frequency,
-                q: F::from(0.7).unwrap(),
+                bandwidth: eq::Bandwidth::Q(F::from(0.7).unwrap()),
                 eq_type: if i == active_index {
                     eq::EqType::Peak
                 } else {
                     eq::EqType::Bypassed
                 },
+                slope: eq::Slope::default(),
+                alignment: eq::Alignment::default(),
+                design: eq::Design::default(),
+                peak_shape: eq::PeakShape::default(),
             }
         })
     }
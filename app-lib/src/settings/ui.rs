@@ -12,8 +12,21 @@ pub struct Settings<F: audio_lib::utils::Float> {
 pub struct ShowOptions {
     pub gain: bool,
     pub signal_gain_spectrum: bool,
+    /// Spectrum of the sidechain input, next to the one of the signal
+    #[serde(default)]
+    pub sidechain_gain_spectrum: bool,
+    /// Overlay the gain of the analog prototypes, to compare against the digital design
+    #[serde(default)]
+    pub analog_gain: bool,
     pub phase: bool,
+    #[serde(default)]
+    pub group_delay: bool,
     pub impulse_response: bool,
+    #[serde(default)]
+    pub step_response: bool,
+    /// Cumulative energy of the impulse response, to judge how long bands ring
+    #[serde(default)]
+    pub cumulative_energy: bool,
     pub poles_and_zeros: bool,
 }
 
@@ -22,6 +35,16 @@ pub struct EqRanges<F: utils::Float> {
     pub db_range: RangeInclusive<F>,
     pub log_frequency_range: RangeInclusive<F>,
     pub q_range: RangeInclusive<F>,
+    pub octaves_range: RangeInclusive<F>,
+    pub shelf_slope_range: RangeInclusive<F>,
+    /// Ranges of the settings of dynamic bands, see audio_lib::dynamic_eq::Dynamics
+    pub threshold_db_range: RangeInclusive<F>,
+    pub ratio_range: RangeInclusive<F>,
+    pub dynamic_range_db_range: RangeInclusive<F>,
+    pub attack_ms_range: RangeInclusive<F>,
+    pub release_ms_range: RangeInclusive<F>,
+    /// Of the gain after all bands, where a processor has one
+    pub output_gain_db_range: RangeInclusive<F>,
 }
 
 impl<F: utils::Float> EqRanges<F> {
@@ -41,10 +64,20 @@ impl<F: utils::Float> Default for EqRanges<F> {
             db_range: db_range,
             log_frequency_range: log_frequency_range,
             q_range: F::from(0.1).unwrap()..=F::from(10).unwrap(),
+            octaves_range: F::from(0.1).unwrap()..=F::from(5).unwrap(),
+            shelf_slope_range: F::from(0.1).unwrap()..=F::from(1.5).unwrap(),
+            threshold_db_range: F::from(-60).unwrap()..=F::ZERO,
+            // ratios below one raise the gain of the band
+            ratio_range: F::from(0.25).unwrap()..=F::from(10).unwrap(),
+            dynamic_range_db_range: F::ZERO..=F::from(24).unwrap(),
+            attack_ms_range: F::from(0.1).unwrap()..=F::from(200).unwrap(),
+            release_ms_range: F::from(5).unwrap()..=F::from(2000).unwrap(),
+            output_gain_db_range: F::from(-24).unwrap()..=F::from(24).unwrap(),
         }
     }
 }
 
+/// Termination of the impulse response, also used for the step response and cumulative energy
 #[derive(Debug, Clone)]
 pub struct ImpulseResponseParams<F: utils::Float> {
     pub eps: F,
@@ -67,8 +100,13 @@ impl ShowOptions {
         Self {
             gain: true,
             signal_gain_spectrum: true,
+            sidechain_gain_spectrum: false,
+            analog_gain: false,
             phase: true,
+            group_delay: true,
             impulse_response: true,
+            step_response: true,
+            cumulative_energy: true,
             poles_and_zeros: true,
         }
     }
@@ -77,8 +115,13 @@ impl ShowOptions {
         Self {
             gain: true,
             signal_gain_spectrum: true,
+            sidechain_gain_spectrum: false,
+            analog_gain: false,
             phase: false,
+            group_delay: false,
             impulse_response: false,
+            step_response: false,
+            cumulative_energy: false,
             poles_and_zeros: false,
         }
     }
@@ -0,0 +1,805 @@
+use crate::biquad::cascade;
+use crate::{eq, utils};
+
+/// s-domain biquad H(s) = (b0 + b1*s + b2*s^2) / (a0 + a1*s + a2*s^2), with s normalized to
+/// the angular frequency 2*pi*frequency. First order sections have b2 = a2 = 0.
+#[derive(Debug, Clone)]
+pub struct AnalogBiquad<F: utils::Float> {
+    pub frequency: F,
+    pub b0: F,
+    pub b1: F,
+    pub b2: F,
+    pub a0: F,
+    pub a1: F,
+    pub a2: F,
+}
+
+/// Analog prototypes of the cookbook formulas, see biquad::coefficients
+impl<F: utils::Float> AnalogBiquad<F> {
+    /// Eq types that need more than one section (see AnalogCascade::from_eq) are
+    /// approximated by a single section here, as in coefficients::Coefficients::from_eq.
+    pub fn from_eq(eq: &eq::Eq<F>) -> Self {
+        let gain_db = eq.gain.db();
+        let frequency = eq.frequency.hz();
+        match eq.eq_type {
+            eq::EqType::Volume => Self::from_volume_db(gain_db),
+            eq::EqType::LowPass => Self::from_lowpass(frequency, eq.q()),
+            eq::EqType::HighPass => Self::from_highpass(frequency, eq.q()),
+            eq::EqType::BandPass => Self::from_bandpass(frequency, eq.q()),
+            eq::EqType::AllPass => Self::from_allpass(frequency, eq.q()),
+            eq::EqType::Notch => Self::from_notch(frequency, eq.q()),
+            eq::EqType::Peak => Self::from_peak_db(
+                gain_db,
+                frequency,
+                eq.peak_shape.cookbook_q(gain_db, eq.q()),
+            ),
+            eq::EqType::LowShelf => Self::from_lowshelf_db(gain_db, frequency, eq.q()),
+            eq::EqType::HighShelf => Self::from_highshelf_db(gain_db, frequency, eq.q()),
+            eq::EqType::Tilt => Self::from_tilt_db(gain_db, frequency),
+            eq::EqType::BandShelf => Self::from_peak_db(gain_db, frequency, eq.q()),
+            eq::EqType::FirstOrderLowPass => Self::from_first_order_lowpass(frequency),
+            eq::EqType::FirstOrderHighPass => Self::from_first_order_highpass(frequency),
+            eq::EqType::FirstOrderAllPass => Self::from_first_order_allpass(frequency),
+            eq::EqType::FirstOrderLowShelf => {
+                Self::from_first_order_lowshelf_db(gain_db, frequency)
+            }
+            eq::EqType::FirstOrderHighShelf => {
+                Self::from_first_order_highshelf_db(gain_db, frequency)
+            }
+            eq::EqType::Bypassed => Self::from_volume_linear(F::ONE),
+        }
+    }
+
+    pub fn from_volume_linear(volume_linear: F) -> Self {
+        Self::new(
+            F::ONE,
+            [volume_linear, F::ZERO, F::ZERO],
+            [F::ONE, F::ZERO, F::ZERO],
+        )
+    }
+
+    pub fn from_volume_db(volume_db: F) -> Self {
+        Self::from_volume_linear(utils::db_to_amplitude(volume_db))
+    }
+
+    pub fn from_lowpass(cutoff_frequency: F, q: F) -> Self {
+        Self::new(
+            cutoff_frequency,
+            [F::ONE, F::ZERO, F::ZERO],
+            [F::ONE, F::ONE / q, F::ONE],
+        )
+    }
+
+    pub fn from_highpass(cutoff_frequency: F, q: F) -> Self {
+        Self::new(
+            cutoff_frequency,
+            [F::ZERO, F::ZERO, F::ONE],
+            [F::ONE, F::ONE / q, F::ONE],
+        )
+    }
+
+    pub fn from_bandpass(frequency: F, q: F) -> Self {
+        Self::new(
+            frequency,
+            [F::ZERO, F::ONE / q, F::ZERO],
+            [F::ONE, F::ONE / q, F::ONE],
+        )
+    }
+
+    pub fn from_allpass(frequency: F, q: F) -> Self {
+        Self::new(
+            frequency,
+            [F::ONE, -F::ONE / q, F::ONE],
+            [F::ONE, F::ONE / q, F::ONE],
+        )
+    }
+
+    pub fn from_notch(frequency: F, q: F) -> Self {
+        Self::new(
+            frequency,
+            [F::ONE, F::ZERO, F::ONE],
+            [F::ONE, F::ONE / q, F::ONE],
+        )
+    }
+
+    pub fn from_peak_linear(gain_linear: F, frequency: F, q: F) -> Self {
+        let a = gain_linear.sqrt();
+        Self::new(
+            frequency,
+            [F::ONE, a / q, F::ONE],
+            [F::ONE, F::ONE / (a * q), F::ONE],
+        )
+    }
+
+    pub fn from_peak_db(gain_db: F, frequency: F, q: F) -> Self {
+        Self::from_peak_linear(utils::db_to_amplitude(gain_db), frequency, q)
+    }
+
+    pub fn from_lowshelf_linear(gain_linear: F, cutoff_frequency: F, q: F) -> Self {
+        let a = gain_linear.sqrt();
+        let sqrt_a_through_q = a.sqrt() / q;
+        Self::new(
+            cutoff_frequency,
+            [a * a, a * sqrt_a_through_q, a],
+            [F::ONE, sqrt_a_through_q, a],
+        )
+    }
+
+    pub fn from_lowshelf_db(gain_db: F, cutoff_frequency: F, q: F) -> Self {
+        Self::from_lowshelf_linear(utils::db_to_amplitude(gain_db), cutoff_frequency, q)
+    }
+
+    pub fn from_highshelf_linear(gain_linear: F, cutoff_frequency: F, q: F) -> Self {
+        let a = gain_linear.sqrt();
+        let sqrt_a_through_q = a.sqrt() / q;
+        Self::new(
+            cutoff_frequency,
+            [a, a * sqrt_a_through_q, a * a],
+            [a, sqrt_a_through_q, F::ONE],
+        )
+    }
+
+    pub fn from_highshelf_db(gain_db: F, cutoff_frequency: F, q: F) -> Self {
+        Self::from_highshelf_linear(utils::db_to_amplitude(gain_db), cutoff_frequency, q)
+    }
+
+    /// See coefficients::Coefficients::from_tilt_db
+    pub fn from_tilt_db(gain_db: F, pivot_frequency: F) -> Self {
+        let mut analog =
+            Self::from_highshelf_db(F::TWO * gain_db, pivot_frequency, F::FRAC_1_SQRT_2());
+        let scale = utils::db_to_amplitude(-gain_db);
+        analog.b0 *= scale;
+        analog.b1 *= scale;
+        analog.b2 *= scale;
+        analog
+    }
+
+    pub fn from_first_order_lowpass(cutoff_frequency: F) -> Self {
+        Self::new(
+            cutoff_frequency,
+            [F::ONE, F::ZERO, F::ZERO],
+            [F::ONE, F::ONE, F::ZERO],
+        )
+    }
+
+    pub fn from_first_order_highpass(cutoff_frequency: F) -> Self {
+        Self::new(
+            cutoff_frequency,
+            [F::ZERO, F::ONE, F::ZERO],
+            [F::ONE, F::ONE, F::ZERO],
+        )
+    }
+
+    pub fn from_first_order_allpass(frequency: F) -> Self {
+        Self::new(
+            frequency,
+            [F::ONE, -F::ONE, F::ZERO],
+            [F::ONE, F::ONE, F::ZERO],
+        )
+    }
+
+    pub fn from_first_order_lowshelf_linear(gain_linear: F, cutoff_frequency: F) -> Self {
+        let sqrt_gain = gain_linear.sqrt();
+        Self::new(
+            cutoff_frequency,
+            [sqrt_gain, F::ONE, F::ZERO],
+            [F::ONE / sqrt_gain, F::ONE, F::ZERO],
+        )
+    }
+
+    pub fn from_first_order_lowshelf_db(gain_db: F, cutoff_frequency: F) -> Self {
+        Self::from_first_order_lowshelf_linear(utils::db_to_amplitude(gain_db), cutoff_frequency)
+    }
+
+    pub fn from_first_order_highshelf_linear(gain_linear: F, cutoff_frequency: F) -> Self {
+        let sqrt_gain = gain_linear.sqrt();
+        Self::new(
+            cutoff_frequency,
+            [sqrt_gain, gain_linear, F::ZERO],
+            [sqrt_gain, F::ONE, F::ZERO],
+        )
+    }
+
+    pub fn from_first_order_highshelf_db(gain_db: F, cutoff_frequency: F) -> Self {
+        Self::from_first_order_highshelf_linear(utils::db_to_amplitude(gain_db), cutoff_frequency)
+    }
+
+    pub fn is_zeroth_order(&self) -> bool {
+        self.is_first_order() && self.a1 == F::ZERO && self.b1 == F::ZERO
+    }
+
+    pub fn is_first_order(&self) -> bool {
+        self.a2 == F::ZERO && self.b2 == F::ZERO
+    }
+
+    /// The numerator is the mirrored denominator, i.e. the zeros are the poles mirrored at the
+    /// imaginary axis
+    pub fn is_allpass(&self) -> bool {
+        self.b0 == self.a0 && self.b1 == -self.a1 && self.b2 == self.a2
+    }
+
+    pub fn frequency_response(&self, frequency: F) -> num::Complex<F> {
+        let s = num::Complex::new(F::ZERO, frequency / self.frequency);
+        let numerator = s * (s * self.b2 + self.b1) + self.b0;
+        let denominator = s * (s * self.a2 + self.a1) + self.a0;
+        numerator / denominator
+    }
+
+    fn new(frequency: F, b: [F; 3], a: [F; 3]) -> Self {
+        Self {
+            frequency,
+            b0: b[0],
+            b1: b[1],
+            b2: b[2],
+            a0: a[0],
+            a1: a[1],
+            a2: a[2],
+        }
+    }
+}
+
+/// Analog prototype of a cascade::Coefficients band.
+#[derive(Debug, Clone)]
+pub struct AnalogCascade<F: utils::Float> {
+    sections: smallvec::SmallVec<[AnalogBiquad<F>; cascade::MAX_NUM_SECTIONS]>,
+}
+
+impl<F: utils::Float> AnalogCascade<F> {
+    pub fn from_eq(eq: &eq::Eq<F>) -> Self {
+        let gain_db = eq.gain.db();
+        let frequency = eq.frequency.hz();
+        match eq.eq_type {
+            eq::EqType::LowPass => Self {
+                sections: cascade::pass_sections(
+                    eq.slope,
+                    eq.alignment,
+                    eq.q(),
+                    || AnalogBiquad::from_first_order_lowpass(frequency),
+                    |section_q| AnalogBiquad::from_lowpass(frequency, section_q),
+                ),
+            },
+            eq::EqType::HighPass => Self {
+                sections: cascade::pass_sections(
+                    eq.slope,
+                    eq.alignment,
+                    eq.q(),
+                    || AnalogBiquad::from_first_order_highpass(frequency),
+                    |section_q| AnalogBiquad::from_highpass(frequency, section_q),
+                ),
+            },
+            eq::EqType::Tilt => {
+                let shelf_q = F::from(cascade::SHELF_Q).unwrap();
+                Self {
+                    sections: smallvec::smallvec![
+                        AnalogBiquad::from_lowshelf_db(-gain_db, frequency, shelf_q),
+                        AnalogBiquad::from_highshelf_db(gain_db, frequency, shelf_q),
+                    ],
+                }
+            }
+            eq::EqType::BandShelf => {
+                let (lower_edge, upper_edge) = cascade::band_edges(frequency, eq.q());
+                Self::from_bandshelf_db(gain_db, lower_edge, upper_edge)
+            }
+            _ => Self::from_section(AnalogBiquad::from_eq(eq)),
+        }
+    }
+
+    /// Like from_eq, for discretising at the sample rate: band shelves keep their upper edge
+    /// below Nyquist, as in cascade::Coefficients::from_bandshelf_db
+    pub fn from_eq_at_sample_rate(eq: &eq::Eq<F>, sample_rate: F) -> Self {
+        match eq.eq_type {
+            eq::EqType::BandShelf => {
+                let (lower_edge, upper_edge) =
+                    cascade::bandshelf_edges(eq.frequency.hz(), eq.q(), sample_rate);
+                Self::from_bandshelf_db(eq.gain.db(), lower_edge, upper_edge)
+            }
+            _ => Self::from_eq(eq),
+        }
+    }
+
+    /// High shelf at the lower edge and inverse high shelf at the upper edge
+    pub fn from_bandshelf_db(gain_db: F, lower_edge: F, upper_edge: F) -> Self {
+        let shelf_q = F::from(cascade::SHELF_Q).unwrap();
+        Self {
+            sections: smallvec::smallvec![
+                AnalogBiquad::from_highshelf_db(gain_db, lower_edge, shelf_q),
+                AnalogBiquad::from_highshelf_db(-gain_db, upper_edge, shelf_q),
+            ],
+        }
+    }
+
+    pub fn from_section(section: AnalogBiquad<F>) -> Self {
+        Self {
+            sections: smallvec::smallvec![section],
+        }
+    }
+
+    pub fn sections(&self) -> &[AnalogBiquad<F>] {
+        &self.sections
+    }
+
+    pub fn frequency_response(&self, frequency: F) -> num::Complex<F> {
+        self.sections
+            .iter()
+            .map(|section| section.frequency_response(frequency))
+            .fold(num::Complex::from(F::ONE), |product, response| {
+                product * response
+            })
+    }
+}
+
+impl<F: utils::Float> From<AnalogBiquad<F>> for AnalogCascade<F> {
+    fn from(section: AnalogBiquad<F>) -> Self {
+        Self::from_section(section)
+    }
+}
+
+pub fn make_frequency_response<F: utils::Float>(
+    analog: impl Into<AnalogCascade<F>>,
+) -> impl Fn(F) -> num::Complex<F> {
+    let analog = analog.into();
+    move |frequency| analog.frequency_response(frequency)
+}
+
+pub mod multiband {
+    use super::*;
+
+    pub fn make_frequency_response<F: utils::Float>(
+        analogs: impl IntoIterator<Item = AnalogCascade<F>>,
+    ) -> impl Fn(F) -> num::Complex<F> {
+        let analogs = analogs.into_iter().collect::<Vec<_>>();
+        move |frequency| {
+            analogs
+                .iter()
+                .map(|analog| analog.frequency_response(frequency))
+                .fold(num::Complex::from(F::ONE), |product, response| {
+                    product * response
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::{coefficients, filter, utils as biquad_utils};
+    use crate::utils::amplitude_to_db;
+    use assert_approx_eq::assert_approx_eq;
+    use more_asserts::{assert_gt, assert_lt};
+    use num::complex::ComplexFloat;
+
+    /// Largest deviation in dB between the analog prototype and a digital section, ignoring
+    /// frequencies where the analog response is below -30 dB
+    fn max_deviation_db(
+        analog: &AnalogBiquad<f64>,
+        digital: coefficients::Coefficients<f64>,
+        sample_rate: f64,
+    ) -> f64 {
+        max_deviation_db_below(analog, digital, sample_rate, 0.499 * sample_rate)
+    }
+
+    fn max_deviation_db_below(
+        analog: &AnalogBiquad<f64>,
+        digital: coefficients::Coefficients<f64>,
+        sample_rate: f64,
+        max_frequency: f64,
+    ) -> f64 {
+        let digital_response = biquad_utils::make_frequency_response(digital, sample_rate);
+        let num_points = 300;
+        (0..num_points)
+            .map(|i| 20.0 * (max_frequency / 20.0).powf(i as f64 / (num_points - 1) as f64))
+            .map(|frequency| {
+                (
+                    amplitude_to_db(analog.frequency_response(frequency).abs()),
+                    amplitude_to_db(digital_response(frequency).abs()),
+                )
+            })
+            .filter(|(analog_db, _)| *analog_db > -30.0)
+            .map(|(analog_db, digital_db)| (analog_db - digital_db).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// The cookbook formulas are the bilinear transform of the analog prototypes, prewarped at
+    /// the prototype frequency, so both have to agree there.
+    #[test]
+    fn prototypes_match_cookbook_at_frequency() {
+        let sample_rate = 48000.0;
+        let frequency = 3000.0;
+        let q = 1.7;
+        let gain_db = 7.5;
+        let pairs = [
+            (
+                AnalogBiquad::from_lowpass(frequency, q),
+                coefficients::Coefficients::from_lowpass(frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_highpass(frequency, q),
+                coefficients::Coefficients::from_highpass(frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_bandpass(frequency, q),
+                coefficients::Coefficients::from_bandpass(frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_allpass(frequency, q),
+                coefficients::Coefficients::from_allpass(frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_peak_db(gain_db, frequency, q),
+                coefficients::Coefficients::from_peak_db(gain_db, frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_lowshelf_db(gain_db, frequency, q),
+                coefficients::Coefficients::from_lowshelf_db(gain_db, frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_highshelf_db(gain_db, frequency, q),
+                coefficients::Coefficients::from_highshelf_db(gain_db, frequency, q, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_tilt_db(gain_db, frequency),
+                coefficients::Coefficients::from_tilt_db(gain_db, frequency, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_first_order_lowpass(frequency),
+                coefficients::Coefficients::from_first_order_lowpass(frequency, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_first_order_highpass(frequency),
+                coefficients::Coefficients::from_first_order_highpass(frequency, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_first_order_allpass(frequency),
+                coefficients::Coefficients::from_first_order_allpass(frequency, sample_rate),
+            ),
+            (
+                AnalogBiquad::from_first_order_lowshelf_db(gain_db, frequency),
+                coefficients::Coefficients::from_first_order_lowshelf_db(
+                    gain_db,
+                    frequency,
+                    sample_rate,
+                ),
+            ),
+            (
+                AnalogBiquad::from_first_order_highshelf_db(gain_db, frequency),
+                coefficients::Coefficients::from_first_order_highshelf_db(
+                    gain_db,
+                    frequency,
+                    sample_rate,
+                ),
+            ),
+        ];
+        for (analog, digital) in pairs {
+            let analog_response = analog.frequency_response(frequency);
+            let digital_response =
+                biquad_utils::make_frequency_response(digital, sample_rate)(frequency);
+            assert_approx_eq!(analog_response.re, digital_response.re, 1e-9);
+            assert_approx_eq!(analog_response.im, digital_response.im, 1e-9);
+        }
+    }
+
+    #[test]
+    fn matched_design_removes_cramping() {
+        let sample_rate = 48000.0;
+        for frequency in [10000.0, 16000.0, 20000.0] {
+            for q in [0.5, 0.707, 2.0] {
+                for gain_db in [-12.0, 12.0] {
+                    let analogs = [
+                        AnalogBiquad::from_peak_db(gain_db, frequency, q),
+                        AnalogBiquad::from_lowshelf_db(gain_db, frequency, 0.5 * q),
+                        AnalogBiquad::from_highshelf_db(gain_db, frequency, 0.5 * q),
+                        AnalogBiquad::from_lowpass(frequency, q),
+                        AnalogBiquad::from_bandpass(frequency, q),
+                    ];
+                    for analog in analogs {
+                        let matched =
+                            coefficients::Coefficients::from_analog_matched(&analog, sample_rate);
+                        assert!(biquad_utils::is_stable(&matched));
+                        assert_lt!(max_deviation_db(&analog, matched, sample_rate), 3.0);
+                    }
+                }
+            }
+        }
+
+        let analog = AnalogBiquad::from_peak_db(12.0, 16000.0, 0.707);
+        let cookbook = coefficients::Coefficients::from_peak_db(12.0, 16000.0, 0.707, sample_rate);
+        let matched = coefficients::Coefficients::from_analog_matched(&analog, sample_rate);
+        let cookbook_deviation = max_deviation_db(&analog, cookbook, sample_rate);
+        let matched_deviation = max_deviation_db(&analog, matched, sample_rate);
+        assert_gt!(cookbook_deviation, 5.0);
+        assert_lt!(matched_deviation, 0.5 * cookbook_deviation);
+    }
+
+    #[test]
+    fn matched_design_keeps_structure() {
+        let sample_rate = 44100.0;
+        let frequency = 5000.0;
+        let q = 3.0;
+
+        let notch = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_analog_matched(
+                &AnalogBiquad::from_notch(frequency, q),
+                sample_rate,
+            ),
+            sample_rate,
+        );
+        assert_approx_eq!(notch(frequency).abs(), 0.0, 1e-9);
+        assert_approx_eq!(notch(0.0).abs(), 1.0, 1e-9);
+
+        let highpass = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_analog_matched(
+                &AnalogBiquad::from_highpass(frequency, q),
+                sample_rate,
+            ),
+            sample_rate,
+        );
+        assert_approx_eq!(highpass(0.0).abs(), 0.0, 1e-9);
+        assert_approx_eq!(
+            amplitude_to_db(highpass(frequency).abs()),
+            amplitude_to_db(q)
+        );
+
+        for analog in [
+            AnalogBiquad::from_allpass(frequency, q),
+            AnalogBiquad::from_first_order_allpass(frequency),
+        ] {
+            let allpass = biquad_utils::make_frequency_response(
+                coefficients::Coefficients::from_analog_matched(&analog, sample_rate),
+                sample_rate,
+            );
+            for i in 0..50 {
+                assert_approx_eq!(allpass(i as f64 * 400.0).abs(), 1.0, 1e-9);
+            }
+        }
+
+        for analog in [
+            AnalogBiquad::from_first_order_lowpass(frequency),
+            AnalogBiquad::from_first_order_highshelf_db(-6.0, frequency),
+            AnalogBiquad::from_volume_db(-3.0),
+        ] {
+            let matched = coefficients::Coefficients::from_analog_matched(&analog, sample_rate);
+            assert_eq!(matched.a2, 0.0);
+            assert_eq!(matched.b2, 0.0);
+            let digital = biquad_utils::make_frequency_response(matched, sample_rate);
+            for frequency in [0.0, 0.5 * sample_rate] {
+                assert_approx_eq!(
+                    digital(frequency).abs(),
+                    analog.frequency_response(frequency).abs(),
+                    1e-9
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matched_cascade_follows_analog_cascade() {
+        let sample_rate = 48000.0;
+        let eq = eq::Eq {
+            gain: eq::Gain::Db(0.0),
+            frequency: eq::Frequency::Hz(2000.0),
+            bandwidth: eq::Bandwidth::Q(std::f64::consts::FRAC_1_SQRT_2),
+            eq_type: eq::EqType::LowPass,
+            slope: eq::Slope::Db48,
+            alignment: eq::Alignment::Butterworth,
+            design: eq::Design::AnalogMatched,
+            peak_shape: eq::PeakShape::default(),
+        };
+        let digital = cascade::Coefficients::from_eq(&eq, sample_rate);
+        assert_eq!(digital.sections().len(), cascade::MAX_NUM_SECTIONS);
+        assert!(biquad_utils::cascade::is_stable(&digital));
+        let digital_response = biquad_utils::cascade::make_frequency_response(digital, sample_rate);
+        let analog_response = make_frequency_response(AnalogCascade::from_eq(&eq));
+        for frequency in [20.0, 500.0, 1000.0, 2000.0] {
+            assert_approx_eq!(
+                amplitude_to_db(digital_response(frequency).abs()),
+                amplitude_to_db(analog_response(frequency).abs()),
+                0.1
+            );
+        }
+        assert_approx_eq!(
+            amplitude_to_db(analog_response(2000.0).abs()),
+            -3.0103,
+            1e-3
+        );
+    }
+
+    fn analog_test_set(frequency: f64) -> [AnalogBiquad<f64>; 9] {
+        [
+            AnalogBiquad::from_lowpass(frequency, 0.707),
+            AnalogBiquad::from_highpass(frequency, 0.707),
+            AnalogBiquad::from_bandpass(frequency, 2.0),
+            AnalogBiquad::from_peak_db(9.0, frequency, 1.5),
+            AnalogBiquad::from_peak_db(-9.0, frequency, 1.5),
+            AnalogBiquad::from_lowshelf_db(6.0, frequency, 0.707),
+            AnalogBiquad::from_highshelf_db(-6.0, frequency, 0.707),
+            AnalogBiquad::from_first_order_lowpass(frequency),
+            AnalogBiquad::from_first_order_highshelf_db(6.0, frequency),
+        ]
+    }
+
+    #[test]
+    fn bilinear_transform_of_prototypes_equals_cookbook() {
+        let sample_rate = 44100.0;
+        for eq_type in eq::EqType::ALL {
+            for slope in eq::Slope::ALL {
+                let eq = eq::Eq {
+                    gain: eq::Gain::Db(-4.5),
+                    frequency: eq::Frequency::Hz(1234.0),
+                    bandwidth: eq::Bandwidth::Q(1.3),
+                    eq_type: *eq_type,
+                    slope: *slope,
+                    alignment: eq::Alignment::default(),
+                    design: eq::Design::Cookbook,
+                    peak_shape: eq::PeakShape::default(),
+                };
+                let cookbook = cascade::Coefficients::from_eq(&eq, sample_rate);
+                let bilinear = cascade::Coefficients::from_analog(
+                    &AnalogCascade::from_eq_at_sample_rate(&eq, sample_rate),
+                    eq::Design::Cookbook,
+                    sample_rate,
+                );
+                assert_eq!(cookbook.sections().len(), bilinear.sections().len());
+                for (cookbook, bilinear) in cookbook.sections().iter().zip(bilinear.sections()) {
+                    assert_approx_eq!(cookbook.a1, bilinear.a1, 1e-9);
+                    assert_approx_eq!(cookbook.a2, bilinear.a2, 1e-9);
+                    assert_approx_eq!(cookbook.b0, bilinear.b0, 1e-9);
+                    assert_approx_eq!(cookbook.b1, bilinear.b1, 1e-9);
+                    assert_approx_eq!(cookbook.b2, bilinear.b2, 1e-9);
+                }
+            }
+        }
+    }
+
+    /// All designs converge to the analog prototype well below Nyquist. Close to Nyquist the
+    /// bilinear transform cramps, matched z misses the zeros at infinity and impulse invariance
+    /// aliases, only the analog matched design stays close.
+    #[test]
+    fn discretization_error() {
+        let sample_rate = 48000.0;
+        let designs = [
+            eq::Design::Cookbook,
+            eq::Design::AnalogMatched,
+            eq::Design::MatchedZ,
+            eq::Design::ImpulseInvariant,
+        ];
+        for design in designs {
+            for analog in analog_test_set(1000.0) {
+                let digital = coefficients::Coefficients::from_analog(&analog, design, sample_rate);
+                assert!(biquad_utils::is_stable(&digital));
+                assert_lt!(
+                    max_deviation_db_below(&analog, digital, sample_rate, 4000.0),
+                    0.5
+                );
+            }
+        }
+
+        let matched_deviation = |analog: &AnalogBiquad<f64>, design| {
+            let digital = coefficients::Coefficients::from_analog(analog, design, sample_rate);
+            assert!(biquad_utils::is_stable(&digital));
+            max_deviation_db(analog, digital, sample_rate)
+        };
+        for analog in analog_test_set(15000.0) {
+            assert_lt!(matched_deviation(&analog, eq::Design::AnalogMatched), 1.5);
+        }
+        let peak = AnalogBiquad::from_peak_db(9.0, 15000.0, 1.5);
+        assert_gt!(matched_deviation(&peak, eq::Design::Cookbook), 3.0);
+        assert_gt!(matched_deviation(&peak, eq::Design::MatchedZ), 2.0);
+        let lowpass = AnalogBiquad::from_lowpass(15000.0, 0.707);
+        assert_gt!(
+            matched_deviation(&lowpass, eq::Design::ImpulseInvariant),
+            3.0
+        );
+    }
+
+    #[test]
+    fn matched_z_maps_poles_and_zeros() {
+        let sample_rate = 48000.0;
+        let frequency = 3000.0;
+        let q = 4.0;
+        let omega0 = crate::utils::omega(frequency, sample_rate);
+
+        let lowpass = coefficients::Coefficients::from_analog_matched_z(
+            &AnalogBiquad::from_lowpass(frequency, q),
+            sample_rate,
+        );
+        // poles at exp(s*T) with s = omega0 * (-1/(2q) +- j*sqrt(1 - 1/(4q^2)))
+        assert_approx_eq!(lowpass.a2, (-omega0 / q).exp(), 1e-12);
+        assert_approx_eq!(
+            lowpass.a1,
+            -2.0 * (-0.5 * omega0 / q).exp() * ((1.0 - 0.25 / (q * q)).sqrt() * omega0).cos(),
+            1e-12
+        );
+        let lowpass = biquad_utils::make_frequency_response(lowpass, sample_rate);
+        assert_approx_eq!(lowpass(0.0).abs(), 1.0, 1e-12);
+        assert_approx_eq!(lowpass(0.5 * sample_rate).abs(), 0.0, 1e-12);
+
+        let notch = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_analog_matched_z(
+                &AnalogBiquad::from_notch(frequency, q),
+                sample_rate,
+            ),
+            sample_rate,
+        );
+        assert_approx_eq!(notch(frequency).abs(), 0.0, 1e-9);
+        assert_approx_eq!(notch(0.0).abs(), 1.0, 1e-12);
+
+        let highpass = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_analog_matched_z(
+                &AnalogBiquad::from_highpass(frequency, q),
+                sample_rate,
+            ),
+            sample_rate,
+        );
+        assert_approx_eq!(highpass(0.0).abs(), 0.0, 1e-12);
+        assert_approx_eq!(highpass(frequency).abs(), q, 1e-9);
+
+        let allpass = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_analog_matched_z(
+                &AnalogBiquad::from_allpass(frequency, q),
+                sample_rate,
+            ),
+            sample_rate,
+        );
+        for i in 0..50 {
+            assert_approx_eq!(allpass(i as f64 * 400.0).abs(), 1.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn impulse_invariant_samples_impulse_response() {
+        let sample_rate = 48000.0;
+        let frequency = 2000.0;
+        let period = crate::utils::omega(frequency, sample_rate);
+        let impulse_response = |coefficients| {
+            let mut filter = filter::Filter::new(coefficients);
+            (0..100)
+                .map(|n| filter.process(if n == 0 { 1.0 } else { 0.0 }))
+                .collect::<Vec<_>>()
+        };
+
+        // 1 / (s^2 + s/q + 1) has the impulse response exp(-sigma*t) * sin(omega*t) / omega
+        let q = 2.0;
+        let sigma = 0.5 / q;
+        let omega = (1.0 - sigma * sigma).sqrt();
+        let lowpass = impulse_response(coefficients::Coefficients::from_analog_impulse_invariant(
+            &AnalogBiquad::from_lowpass(frequency, q),
+            sample_rate,
+        ));
+        for (n, sample) in lowpass.iter().enumerate() {
+            let t = n as f64 * period;
+            assert_approx_eq!(
+                *sample,
+                period * (-sigma * t).exp() * (omega * t).sin() / omega,
+                1e-12
+            );
+        }
+
+        // s/q / (s^2 + s/q + 1) jumps to 1/q at t = 0, the first sample gets half of it
+        let bandpass = impulse_response(coefficients::Coefficients::from_analog_impulse_invariant(
+            &AnalogBiquad::from_bandpass(frequency, q),
+            sample_rate,
+        ));
+        assert_approx_eq!(bandpass[0], 0.5 * period / q, 1e-12);
+        for (n, sample) in bandpass.iter().enumerate().skip(1) {
+            let t = n as f64 * period;
+            assert_approx_eq!(
+                *sample,
+                period / q
+                    * (-sigma * t).exp()
+                    * ((omega * t).cos() - sigma * (omega * t).sin() / omega),
+                1e-12
+            );
+        }
+
+        // 1 / (s + 1) jumps to 1 at t = 0, the first sample gets half of it
+        let first_order_lowpass =
+            impulse_response(coefficients::Coefficients::from_analog_impulse_invariant(
+                &AnalogBiquad::from_first_order_lowpass(frequency),
+                sample_rate,
+            ));
+        assert_approx_eq!(first_order_lowpass[0], 0.5 * period, 1e-12);
+        for (n, sample) in first_order_lowpass.iter().enumerate().skip(1) {
+            assert_approx_eq!(*sample, period * (-(n as f64) * period).exp(), 1e-12);
+        }
+    }
+}
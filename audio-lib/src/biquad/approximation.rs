@@ -0,0 +1,578 @@
+use crate::biquad::{cascade, pole_zero};
+use crate::utils;
+use num::Complex;
+
+/// Classic filter approximations beyond Butterworth (see cascade::butterworth_q).
+/// ripple_db is the maximum attenuation in the passband, stopband_attenuation_db the minimum
+/// attenuation in the stopband.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Approximation<F: utils::Float> {
+    /// Equiripple passband, monotonic stopband. The cutoff frequency is where the gain leaves
+    /// the passband ripple.
+    ChebyshevI { ripple_db: F },
+    /// Monotonic passband, equiripple stopband. The cutoff frequency is where the gain reaches
+    /// the stopband attenuation.
+    ChebyshevII { stopband_attenuation_db: F },
+    /// Equiripple passband and stopband, the steepest transition for a given order. The cutoff
+    /// frequency is where the gain leaves the passband ripple.
+    Elliptic {
+        ripple_db: F,
+        stopband_attenuation_db: F,
+    },
+}
+
+/// Frequencies are the band edges as defined by the approximation
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Passband<F: utils::Float> {
+    LowPass {
+        cutoff_frequency: F,
+    },
+    HighPass {
+        cutoff_frequency: F,
+    },
+    /// Twice the order of the prototype
+    BandPass {
+        lower_frequency: F,
+        upper_frequency: F,
+    },
+}
+
+/// Poles, zeros and gain of H(s) = gain * prod(s - zero) / prod(s - pole), conjugate pairs
+/// are both contained
+#[derive(Debug, Clone)]
+struct AnalogPoleZero {
+    zeros: Vec<Complex<f64>>,
+    poles: Vec<Complex<f64>>,
+    gain: f64,
+}
+
+impl<F: utils::Float> Approximation<F> {
+    /// The design is done with the analog prototype, which is transformed to the passband and
+    /// discretised with the bilinear transform, prewarped at the band edges.
+    /// The design is done in f64, as the roots of higher orders are badly conditioned in f32.
+    pub fn pole_zero(
+        &self,
+        order: usize,
+        passband: Passband<F>,
+        sample_rate: F,
+    ) -> pole_zero::PoleZero<F> {
+        let to_f64 = |value: F| value.to_f64().unwrap();
+        let sample_rate = to_f64(sample_rate);
+        let prewarp = |frequency: F| {
+            2.0 * sample_rate * (utils::omega(to_f64(frequency), sample_rate) / 2.0).tan()
+        };
+        let prototype = self.analog_prototype(order);
+        let analog = match passband {
+            Passband::LowPass { cutoff_frequency } => {
+                lowpass_to_lowpass(&prototype, prewarp(cutoff_frequency))
+            }
+            Passband::HighPass { cutoff_frequency } => {
+                lowpass_to_highpass(&prototype, prewarp(cutoff_frequency))
+            }
+            Passband::BandPass {
+                lower_frequency,
+                upper_frequency,
+            } => lowpass_to_bandpass(
+                &prototype,
+                prewarp(lower_frequency),
+                prewarp(upper_frequency),
+            ),
+        };
+        let digital = bilinear(&analog, sample_rate);
+        let from_roots = |roots: &[Complex<f64>]| {
+            one_of_each_pair(roots)
+                .map(|root| Complex::new(F::from(root.re).unwrap(), F::from(root.im).unwrap()))
+                .collect()
+        };
+        pole_zero::PoleZero::new(
+            from_roots(&digital.zeros),
+            from_roots(&digital.poles),
+            F::from(digital.gain).unwrap(),
+        )
+    }
+
+    pub fn cascade(
+        &self,
+        order: usize,
+        passband: Passband<F>,
+        sample_rate: F,
+    ) -> cascade::Coefficients<F> {
+        self.pole_zero(order, passband, sample_rate).to_cascade()
+    }
+
+    /// Low pass prototype with the cutoff at 1 rad/s
+    fn analog_prototype(&self, order: usize) -> AnalogPoleZero {
+        let to_f64 = |value: F| value.to_f64().unwrap();
+        match *self {
+            Self::ChebyshevI { ripple_db } => chebyshev_i_prototype(order, to_f64(ripple_db)),
+            Self::ChebyshevII {
+                stopband_attenuation_db,
+            } => chebyshev_ii_prototype(order, to_f64(stopband_attenuation_db)),
+            Self::Elliptic {
+                ripple_db,
+                stopband_attenuation_db,
+            } => elliptic_prototype(order, to_f64(ripple_db), to_f64(stopband_attenuation_db)),
+        }
+    }
+}
+
+/// epsilon of |H|^2 = 1 / (1 + epsilon^2) at the given attenuation
+fn epsilon(attenuation_db: f64) -> f64 {
+    (10.0_f64.powf(0.1 * attenuation_db) - 1.0).sqrt()
+}
+
+/// Angles pi * (2k + 1) / (2 * order) of the Butterworth poles, k = 0..order
+fn butterworth_angles(order: usize) -> impl Iterator<Item = f64> {
+    (0..order).map(move |k| std::f64::consts::PI * (2 * k + 1) as f64 / (2 * order) as f64)
+}
+
+/// Gain that makes the response at DC 1, or 1 / sqrt(1 + epsilon^2) for even orders of
+/// approximations with passband ripple
+fn with_dc_gain(
+    zeros: Vec<Complex<f64>>,
+    poles: Vec<Complex<f64>>,
+    dc_gain: f64,
+) -> AnalogPoleZero {
+    let product = |roots: &[Complex<f64>]| {
+        roots
+            .iter()
+            .fold(Complex::from(1.0), |product, root| product * -root)
+    };
+    let gain = (product(&poles) / product(&zeros)).re * dc_gain;
+    AnalogPoleZero { zeros, poles, gain }
+}
+
+fn chebyshev_i_prototype(order: usize, ripple_db: f64) -> AnalogPoleZero {
+    let epsilon = epsilon(ripple_db);
+    let mu = (1.0 / epsilon).asinh() / order as f64;
+    let poles = butterworth_angles(order)
+        .map(|angle| Complex::new(-mu.sinh() * angle.sin(), mu.cosh() * angle.cos()))
+        .collect();
+    let dc_gain = if order.is_multiple_of(2) {
+        1.0 / (1.0 + epsilon * epsilon).sqrt()
+    } else {
+        1.0
+    };
+    with_dc_gain(Vec::new(), poles, dc_gain)
+}
+
+/// Inverse Chebyshev: the poles are the inverted Chebyshev I poles for the stopband ripple,
+/// the zeros are on the imaginary axis at the inverted Chebyshev nodes
+fn chebyshev_ii_prototype(order: usize, stopband_attenuation_db: f64) -> AnalogPoleZero {
+    let mu = epsilon(stopband_attenuation_db).asinh() / order as f64;
+    let poles = butterworth_angles(order)
+        .map(|angle| Complex::new(-mu.sinh() * angle.sin(), mu.cosh() * angle.cos()).inv())
+        .collect();
+    let zeros = butterworth_angles(order)
+        .enumerate()
+        // the middle node of odd orders is a zero at infinity
+        .filter(|(k, _)| 2 * k + 1 != order)
+        .map(|(_, angle)| Complex::new(0.0, 1.0 / angle.cos()))
+        .collect();
+    with_dc_gain(zeros, poles, 1.0)
+}
+
+/// After Orfanidis, "Lecture Notes on Elliptic Filter Design" (2006), with the passband edge at
+/// 1 rad/s
+fn elliptic_prototype(
+    order: usize,
+    ripple_db: f64,
+    stopband_attenuation_db: f64,
+) -> AnalogPoleZero {
+    let passband_epsilon = epsilon(ripple_db);
+    let stopband_epsilon = epsilon(stopband_attenuation_db);
+    let k1 = passband_epsilon / stopband_epsilon;
+    let k = elliptic::degree(order, k1);
+    let j = Complex::i();
+
+    let half_order = order / 2;
+    let u = |i: usize| (2 * i + 1) as f64 / order as f64;
+    let v0 = (-j * elliptic::asne(j / passband_epsilon, k1) / order as f64).re;
+
+    let mut zeros = Vec::with_capacity(2 * half_order);
+    let mut poles = Vec::with_capacity(order);
+    for i in 0..half_order {
+        let zero = j / (elliptic::cde(Complex::from(u(i)), k) * k);
+        zeros.extend([zero, zero.conj()]);
+        let pole = j * elliptic::cde(Complex::new(u(i), -v0), k);
+        poles.extend([pole, pole.conj()]);
+    }
+    if !order.is_multiple_of(2) {
+        poles.push(Complex::from(
+            (j * elliptic::sne(Complex::new(0.0, v0), k)).re,
+        ));
+    }
+    let dc_gain = if order.is_multiple_of(2) {
+        1.0 / (1.0 + passband_epsilon * passband_epsilon).sqrt()
+    } else {
+        1.0
+    };
+    with_dc_gain(zeros, poles, dc_gain)
+}
+
+/// s -> s / cutoff
+fn lowpass_to_lowpass(prototype: &AnalogPoleZero, cutoff: f64) -> AnalogPoleZero {
+    let degree = prototype.poles.len() as i32 - prototype.zeros.len() as i32;
+    AnalogPoleZero {
+        zeros: prototype.zeros.iter().map(|zero| zero * cutoff).collect(),
+        poles: prototype.poles.iter().map(|pole| pole * cutoff).collect(),
+        gain: prototype.gain * cutoff.powi(degree),
+    }
+}
+
+/// s -> cutoff / s, the zeros at infinity move to DC
+fn lowpass_to_highpass(prototype: &AnalogPoleZero, cutoff: f64) -> AnalogPoleZero {
+    let product = |roots: &[Complex<f64>]| {
+        roots
+            .iter()
+            .fold(Complex::from(1.0), |product, root| product * -root)
+    };
+    let mut zeros = prototype
+        .zeros
+        .iter()
+        .map(|zero| cutoff / zero)
+        .collect::<Vec<_>>();
+    zeros.resize(prototype.poles.len(), Complex::from(0.0));
+    AnalogPoleZero {
+        zeros,
+        poles: prototype.poles.iter().map(|pole| cutoff / pole).collect(),
+        gain: prototype.gain * (product(&prototype.zeros) / product(&prototype.poles)).re,
+    }
+}
+
+/// s -> (s^2 + center^2) / (bandwidth * s), each root splits in two, the zeros at infinity
+/// are split between DC and infinity
+fn lowpass_to_bandpass(prototype: &AnalogPoleZero, lower: f64, upper: f64) -> AnalogPoleZero {
+    let bandwidth = upper - lower;
+    let center_squared = lower * upper;
+    let split = |roots: &[Complex<f64>]| {
+        roots
+            .iter()
+            .flat_map(|root| {
+                let half = root * bandwidth / 2.0;
+                let offset = (half * half - center_squared).sqrt();
+                [half + offset, half - offset]
+            })
+            .collect::<Vec<_>>()
+    };
+    let degree = prototype.poles.len() - prototype.zeros.len();
+    let mut zeros = split(&prototype.zeros);
+    zeros.extend(std::iter::repeat_n(Complex::from(0.0), degree));
+    AnalogPoleZero {
+        zeros,
+        poles: split(&prototype.poles),
+        gain: prototype.gain * bandwidth.powi(degree as i32),
+    }
+}
+
+/// s = 2 * fs * (1 - z^-1) / (1 + z^-1), zeros at infinity are placed at Nyquist.
+/// Returns the digital roots in z and the gain of the product form in z^-1 (see
+/// pole_zero::PoleZero)
+fn bilinear(analog: &AnalogPoleZero, sample_rate: f64) -> AnalogPoleZero {
+    let c = 2.0 * sample_rate;
+    let map = |roots: &[Complex<f64>]| {
+        roots
+            .iter()
+            .map(|root| (c + root) / (c - root))
+            .collect::<Vec<_>>()
+    };
+    let product = |roots: &[Complex<f64>]| {
+        roots
+            .iter()
+            .fold(Complex::from(1.0), |product, root| product * (c - root))
+    };
+    let mut zeros = map(&analog.zeros);
+    zeros.resize(analog.poles.len(), Complex::from(-1.0));
+    AnalogPoleZero {
+        zeros,
+        poles: map(&analog.poles),
+        gain: analog.gain * (product(&analog.zeros) / product(&analog.poles)).re,
+    }
+}
+
+/// Real roots and the roots with positive imaginary part, such that each conjugate pair is
+/// given once
+fn one_of_each_pair(roots: &[Complex<f64>]) -> impl Iterator<Item = Complex<f64>> + '_ {
+    roots.iter().filter_map(|root| {
+        let tolerance = 1e-9 * (1.0 + root.norm());
+        if root.im.abs() <= tolerance {
+            Some(Complex::from(root.re))
+        } else if root.im > 0.0 {
+            Some(*root)
+        } else {
+            None
+        }
+    })
+}
+
+/// Jacobi elliptic functions with normalized argument (u = 1 is the quarter period K), using
+/// the descending Landen transformation, see Orfanidis
+mod elliptic {
+    use num::Complex;
+
+    /// Moduli of the descending Landen transformation, down to the point they vanish
+    fn landen(k: f64) -> Vec<f64> {
+        let mut moduli = Vec::new();
+        let mut k = k;
+        while k > f64::EPSILON && moduli.len() < 16 {
+            k = (k / (1.0 + (1.0 - k * k).sqrt())).powi(2);
+            moduli.push(k);
+        }
+        moduli
+    }
+
+    fn ascending(w: Complex<f64>, k: f64) -> Complex<f64> {
+        landen(k)
+            .iter()
+            .rev()
+            .fold(w, |w, v| w * (1.0 + v) / (w * w * *v + 1.0))
+    }
+
+    /// cd(u * K, k)
+    pub fn cde(u: Complex<f64>, k: f64) -> Complex<f64> {
+        ascending((u * std::f64::consts::FRAC_PI_2).cos(), k)
+    }
+
+    /// sn(u * K, k)
+    pub fn sne(u: Complex<f64>, k: f64) -> Complex<f64> {
+        ascending((u * std::f64::consts::FRAC_PI_2).sin(), k)
+    }
+
+    /// Complete elliptic integral K(k)
+    fn complete(k: f64) -> f64 {
+        landen(k).iter().map(|v| 1.0 + v).product::<f64>() * std::f64::consts::FRAC_PI_2
+    }
+
+    /// Inverse of cde, reduced to the fundamental period
+    pub fn acde(w: Complex<f64>, k: f64) -> Complex<f64> {
+        let moduli = landen(k);
+        let mut w = w;
+        let mut previous = k;
+        for v in moduli.iter() {
+            w = w / ((-w * w * previous * previous + 1.0).sqrt() + 1.0) * 2.0 / (1.0 + v);
+            previous = *v;
+        }
+        let u = w.acos() / std::f64::consts::FRAC_PI_2;
+        let ratio = complete((1.0 - k * k).sqrt()) / complete(k);
+        let symmetric_remainder = |x: f64, y: f64| x - y * (x / y).round();
+        Complex::new(
+            symmetric_remainder(u.re, 4.0),
+            symmetric_remainder(u.im, 2.0 * ratio),
+        )
+    }
+
+    /// Inverse of sne
+    pub fn asne(w: Complex<f64>, k: f64) -> Complex<f64> {
+        -acde(w, k) + 1.0
+    }
+
+    /// Solves the degree equation N * K'/K = K1'/K1 for the selectivity k, given the
+    /// discrimination k1
+    pub fn degree(order: usize, k1: f64) -> f64 {
+        let k1_complement = (1.0 - k1 * k1).sqrt();
+        let complement = (0..order / 2)
+            .map(|i| {
+                sne(
+                    Complex::from((2 * i + 1) as f64 / order as f64),
+                    k1_complement,
+                )
+                .re
+            })
+            .fold(k1_complement.powi(order as i32), |product, sn| {
+                product * sn.powi(4)
+            });
+        (1.0 - complement * complement).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::utils as biquad_utils;
+    use crate::utils::amplitude_to_db;
+    use assert_approx_eq::assert_approx_eq;
+    use more_asserts::{assert_ge, assert_le};
+    use num::complex::ComplexFloat;
+
+    const SAMPLE_RATE: f64 = 48000.0;
+
+    /// Gains in dB of the cascade at the given frequencies, after checking that the sections
+    /// are stable and reproduce the pole zero representation
+    fn gains_db(
+        approximation: Approximation<f64>,
+        order: usize,
+        passband: Passband<f64>,
+        frequencies: impl Iterator<Item = f64>,
+    ) -> Vec<f64> {
+        let pole_zero = approximation.pole_zero(order, passband, SAMPLE_RATE);
+        let coefficients = pole_zero.to_cascade();
+        assert!(biquad_utils::cascade::is_stable(&coefficients));
+        let frequency_response =
+            biquad_utils::cascade::make_frequency_response(coefficients, SAMPLE_RATE);
+        frequencies
+            .map(|frequency| {
+                let response = frequency_response(frequency);
+                let expected = pole_zero.frequency_response(frequency, SAMPLE_RATE);
+                assert_approx_eq!(response.abs(), expected.abs(), 1e-6);
+                amplitude_to_db(response.abs())
+            })
+            .collect()
+    }
+
+    fn linear_frequencies(from: f64, to: f64) -> impl Iterator<Item = f64> {
+        (0..=200).map(move |i| from + (to - from) * i as f64 / 200.0)
+    }
+
+    #[test]
+    fn chebyshev_i_ripples_in_passband() {
+        for order in [3, 4, 7] {
+            let approximation = Approximation::ChebyshevI { ripple_db: 1.0 };
+            let passband = Passband::LowPass {
+                cutoff_frequency: 2000.0,
+            };
+            let passband_gains = gains_db(
+                approximation,
+                order,
+                passband,
+                linear_frequencies(0.0, 2000.0),
+            );
+            for gain in passband_gains.iter() {
+                assert_le!(*gain, 1e-9);
+                assert_ge!(*gain, -1.0 - 1e-9);
+            }
+            let expected_dc = if order.is_multiple_of(2) { -1.0 } else { 0.0 };
+            assert_approx_eq!(passband_gains[0], expected_dc, 1e-6);
+            assert_approx_eq!(*passband_gains.last().unwrap(), -1.0, 1e-6);
+
+            let highpass_gains = gains_db(
+                approximation,
+                order,
+                Passband::HighPass {
+                    cutoff_frequency: 2000.0,
+                },
+                [2000.0, 0.0].into_iter(),
+            );
+            assert_approx_eq!(highpass_gains[0], -1.0, 1e-6);
+            assert_le!(highpass_gains[1], -100.0);
+        }
+    }
+
+    #[test]
+    fn chebyshev_ii_ripples_in_stopband() {
+        let approximation = Approximation::ChebyshevII {
+            stopband_attenuation_db: 40.0,
+        };
+        for order in [4, 5] {
+            let passband = Passband::LowPass {
+                cutoff_frequency: 5000.0,
+            };
+            let stopband_gains = gains_db(
+                approximation,
+                order,
+                passband,
+                linear_frequencies(5000.0, 24000.0),
+            );
+            assert_approx_eq!(stopband_gains[0], -40.0, 1e-6);
+            for gain in stopband_gains.iter() {
+                assert_le!(*gain, -40.0 + 1e-6);
+            }
+            let passband_gains = gains_db(approximation, order, passband, [0.0, 100.0].into_iter());
+            assert_approx_eq!(passband_gains[0], 0.0, 1e-9);
+            assert_le!(passband_gains[1], 1e-9);
+        }
+    }
+
+    #[test]
+    fn elliptic_ripples_in_both_bands() {
+        let approximation = Approximation::Elliptic {
+            ripple_db: 0.5,
+            stopband_attenuation_db: 60.0,
+        };
+        for order in [4, 5, 6] {
+            let passband = Passband::LowPass {
+                cutoff_frequency: 3000.0,
+            };
+            let passband_gains = gains_db(
+                approximation,
+                order,
+                passband,
+                linear_frequencies(0.0, 3000.0),
+            );
+            for gain in passband_gains.iter() {
+                assert_le!(*gain, 1e-9);
+                assert_ge!(*gain, -0.5 - 1e-9);
+            }
+            assert_approx_eq!(*passband_gains.last().unwrap(), -0.5, 1e-6);
+
+            // all finite zeros are on the unit circle, the stopband reaches -60 dB
+            let pole_zero = approximation.pole_zero(order, passband, SAMPLE_RATE);
+            for zero in pole_zero.zeros.iter() {
+                assert_approx_eq!(zero.norm(), 1.0, 1e-9);
+            }
+            let stopband_gains = gains_db(
+                approximation,
+                order,
+                passband,
+                linear_frequencies(9000.0, 24000.0),
+            );
+            for gain in stopband_gains.iter() {
+                assert_le!(*gain, -60.0 + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn elliptic_is_steeper_than_chebyshev() {
+        let order = 5;
+        let passband = Passband::LowPass {
+            cutoff_frequency: 1000.0,
+        };
+        let at_1500_hz =
+            |approximation| gains_db(approximation, order, passband, [1500.0].into_iter())[0];
+        let chebyshev = at_1500_hz(Approximation::ChebyshevI { ripple_db: 1.0 });
+        let elliptic = at_1500_hz(Approximation::Elliptic {
+            ripple_db: 1.0,
+            stopband_attenuation_db: 60.0,
+        });
+        assert_le!(elliptic, chebyshev - 3.0);
+    }
+
+    #[test]
+    fn bandpass_has_edges_at_ripple() {
+        let approximations = [
+            Approximation::ChebyshevI { ripple_db: 0.5 },
+            Approximation::Elliptic {
+                ripple_db: 0.5,
+                stopband_attenuation_db: 50.0,
+            },
+        ];
+        for approximation in approximations {
+            let passband = Passband::BandPass {
+                lower_frequency: 500.0,
+                upper_frequency: 4000.0,
+            };
+            let coefficients = approximation.cascade(3, passband, SAMPLE_RATE);
+            assert_eq!(coefficients.sections().len(), 3);
+            let gains = gains_db(
+                approximation,
+                3,
+                passband,
+                [500.0, 4000.0, 0.0, 23999.0].into_iter(),
+            );
+            assert_approx_eq!(gains[0], -0.5, 1e-6);
+            assert_approx_eq!(gains[1], -0.5, 1e-6);
+            assert_le!(gains[2], -50.0);
+            assert_le!(gains[3], -50.0);
+            let passband_gains = gains_db(
+                approximation,
+                3,
+                passband,
+                linear_frequencies(500.0, 4000.0),
+            );
+            for gain in passband_gains {
+                assert_le!(gain, 1e-9);
+                assert_ge!(gain, -0.5 - 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,677 @@
+use crate::biquad::{analog, coefficients, filter, utils as biquad_utils};
+use crate::{eq, utils};
+
+/// 48 dB/oct need 4 second order sections, which is the steepest slope we support
+pub const MAX_NUM_SECTIONS: usize = 4;
+
+/// With q = 1/sqrt(2) (shelf slope S = 1), cookbook shelves are as steep as possible without
+/// overshooting
+pub(crate) const SHELF_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+#[allow(type_alias_bounds)]
+type Sections<F: utils::Float> =
+    smallvec::SmallVec<[coefficients::Coefficients<F>; MAX_NUM_SECTIONS]>;
+
+pub(crate) type SectionsOf<S> = smallvec::SmallVec<[S; MAX_NUM_SECTIONS]>;
+
+/// Coefficients of a series of biquad sections, that together make up one band.
+#[derive(Debug, Clone)]
+pub struct Coefficients<F: utils::Float> {
+    sections: Sections<F>,
+}
+
+impl<F: utils::Float> Coefficients<F> {
+    pub fn from_eq(eq: &eq::Eq<F>, sample_rate: F) -> Self {
+        if eq.design != eq::Design::Cookbook {
+            return Self::from_analog(
+                &analog::AnalogCascade::from_eq_at_sample_rate(eq, sample_rate),
+                eq.design,
+                sample_rate,
+            );
+        }
+        let gain_db = eq.gain.db();
+        let frequency = eq.frequency.hz();
+        match eq.eq_type {
+            eq::EqType::LowPass => {
+                Self::from_lowpass(frequency, eq.q(), eq.slope, eq.alignment, sample_rate)
+            }
+            eq::EqType::HighPass => {
+                Self::from_highpass(frequency, eq.q(), eq.slope, eq.alignment, sample_rate)
+            }
+            eq::EqType::Tilt => Self::from_tilt_db(gain_db, frequency, sample_rate),
+            eq::EqType::BandShelf => {
+                Self::from_bandshelf_db(gain_db, frequency, eq.q(), sample_rate)
+            }
+            _ => Self::from_section(coefficients::Coefficients::from_eq(eq, sample_rate)),
+        }
+    }
+
+    /// See coefficients::Coefficients::try_from_eq
+    pub fn try_from_eq(eq: &eq::Eq<F>, sample_rate: F) -> Result<Self, coefficients::Error<F>> {
+        coefficients::validate_eq(eq, sample_rate)?;
+        let coefficients = Self::from_eq(eq, sample_rate);
+        if coefficients
+            .sections
+            .iter()
+            .all(|section| section.is_finite() && biquad_utils::is_stable(section))
+        {
+            Ok(coefficients)
+        } else {
+            Err(coefficients::Error::Unstable)
+        }
+    }
+
+    pub fn from_section(section: coefficients::Coefficients<F>) -> Self {
+        Self {
+            sections: smallvec::smallvec![section],
+        }
+    }
+
+    /// Each section is designed with coefficients::Coefficients::from_analog
+    pub fn from_analog(
+        analog: &analog::AnalogCascade<F>,
+        design: eq::Design,
+        sample_rate: F,
+    ) -> Self {
+        Self {
+            sections: analog
+                .sections()
+                .iter()
+                .map(|section| {
+                    coefficients::Coefficients::from_analog(section, design, sample_rate)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn muted() -> Self {
+        Self::from_section(coefficients::Coefficients::muted())
+    }
+
+    pub fn passthrough() -> Self {
+        Self::from_section(coefficients::Coefficients::passthrough())
+    }
+
+    /// q works as resonance control: it scales the q of the section with the highest q,
+    /// such that q = 1/sqrt(2) gives the plain alignment, and 12 dB/oct Butterworth is
+    /// identical to the cookbook low pass.
+    pub fn from_lowpass(
+        cutoff_frequency: F,
+        q: F,
+        slope: eq::Slope,
+        alignment: eq::Alignment,
+        sample_rate: F,
+    ) -> Self {
+        Self::from_pass_sections(
+            slope,
+            alignment,
+            q,
+            || coefficients::Coefficients::from_first_order_lowpass(cutoff_frequency, sample_rate),
+            |section_q| {
+                coefficients::Coefficients::from_lowpass(cutoff_frequency, section_q, sample_rate)
+            },
+        )
+    }
+
+    /// See from_lowpass for how q is applied.
+    pub fn from_highpass(
+        cutoff_frequency: F,
+        q: F,
+        slope: eq::Slope,
+        alignment: eq::Alignment,
+        sample_rate: F,
+    ) -> Self {
+        Self::from_pass_sections(
+            slope,
+            alignment,
+            q,
+            || coefficients::Coefficients::from_first_order_highpass(cutoff_frequency, sample_rate),
+            |section_q| {
+                coefficients::Coefficients::from_highpass(cutoff_frequency, section_q, sample_rate)
+            },
+        )
+    }
+
+    /// Matched low and high shelf around the pivot frequency: the low end gets -gain_db,
+    /// the high end +gain_db, and the pivot stays at 0 dB.
+    pub fn from_tilt_db(gain_db: F, pivot_frequency: F, sample_rate: F) -> Self {
+        let shelf_q = F::from(SHELF_Q).unwrap();
+        Self {
+            sections: smallvec::smallvec![
+                coefficients::Coefficients::from_lowshelf_db(
+                    -gain_db,
+                    pivot_frequency,
+                    shelf_q,
+                    sample_rate
+                ),
+                coefficients::Coefficients::from_highshelf_db(
+                    gain_db,
+                    pivot_frequency,
+                    shelf_q,
+                    sample_rate
+                ),
+            ],
+        }
+    }
+
+    /// Flat boost or cut between the band edges (see band_edges), built from a high shelf at
+    /// the lower edge and an inverse high shelf at the upper edge.
+    pub fn from_bandshelf_db(gain_db: F, frequency: F, q: F, sample_rate: F) -> Self {
+        let shelf_q = F::from(SHELF_Q).unwrap();
+        let (lower_edge, upper_edge) = bandshelf_edges(frequency, q, sample_rate);
+        Self {
+            sections: smallvec::smallvec![
+                coefficients::Coefficients::from_highshelf_db(
+                    gain_db,
+                    lower_edge,
+                    shelf_q,
+                    sample_rate
+                ),
+                coefficients::Coefficients::from_highshelf_db(
+                    -gain_db,
+                    upper_edge,
+                    shelf_q,
+                    sample_rate
+                ),
+            ],
+        }
+    }
+
+    pub fn from_sections(
+        sections: impl IntoIterator<Item = coefficients::Coefficients<F>>,
+    ) -> Self {
+        Self {
+            sections: sections.into_iter().collect(),
+        }
+    }
+
+    pub fn sections(&self) -> &[coefficients::Coefficients<F>] {
+        &self.sections
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Coefficients<G> {
+        Coefficients::from_sections(self.sections.iter().map(|section| section.cast()))
+    }
+
+    pub fn into_sections(self) -> impl Iterator<Item = coefficients::Coefficients<F>> {
+        self.sections.into_iter()
+    }
+
+    fn from_pass_sections(
+        slope: eq::Slope,
+        alignment: eq::Alignment,
+        q: F,
+        make_first_order_section: impl Fn() -> coefficients::Coefficients<F>,
+        make_second_order_section: impl Fn(F) -> coefficients::Coefficients<F>,
+    ) -> Self {
+        Self {
+            sections: pass_sections(
+                slope,
+                alignment,
+                q,
+                make_first_order_section,
+                make_second_order_section,
+            ),
+        }
+    }
+}
+
+impl<F: utils::Float> From<coefficients::Coefficients<F>> for Coefficients<F> {
+    fn from(section: coefficients::Coefficients<F>) -> Self {
+        Self::from_section(section)
+    }
+}
+
+/// Sections of a low pass or high pass with the given slope and alignment, shared by the
+/// digital and the analog (see analog::AnalogCascade) cascades.
+pub(crate) fn pass_sections<F: utils::Float, S: Clone>(
+    slope: eq::Slope,
+    alignment: eq::Alignment,
+    q: F,
+    make_first_order_section: impl Fn() -> S,
+    make_second_order_section: impl Fn(F) -> S,
+) -> SectionsOf<S> {
+    let order = slope.order();
+    let squared_butterworth = alignment.for_slope(slope) == eq::Alignment::LinkwitzRiley;
+    let butterworth_order = if squared_butterworth {
+        order / 2
+    } else {
+        order
+    };
+
+    let butterworth_sections = |resonance: F| {
+        let mut sections = SectionsOf::new();
+        if !butterworth_order.is_multiple_of(2) {
+            sections.push(make_first_order_section());
+        }
+        for k in 0..butterworth_order / 2 {
+            let mut section_q = butterworth_q(butterworth_order, k);
+            if k == 0 {
+                section_q *= resonance;
+            }
+            sections.push(make_second_order_section(section_q));
+        }
+        sections
+    };
+    let mut sections = butterworth_sections(q * F::SQRT_2());
+    if squared_butterworth {
+        // the resonance applies to one of the squared filters only, so that the peak is the
+        // same as for Butterworth
+        sections.extend(butterworth_sections(F::ONE));
+    }
+    sections
+}
+
+/// q of the k-th second order section of a Butterworth filter of the given order,
+/// k = 0 being the one with the highest q.
+pub fn butterworth_q<F: utils::Float>(order: usize, k: usize) -> F {
+    let angle = F::PI() * F::from(2 * k + 1).unwrap() / F::from(2 * order).unwrap();
+    F::ONE / (F::TWO * angle.sin())
+}
+
+/// Lower and upper edge of a band around the center frequency, placed symmetrically in log
+/// frequency, with q defining the bandwidth as for band passes (upper - lower = center / q).
+pub fn band_edges<F: utils::Float>(center_frequency: F, q: F) -> (F, F) {
+    let half_through_q = F::ONE_HALF / q;
+    let ratio = half_through_q + (F::ONE + half_through_q * half_through_q).sqrt();
+    (center_frequency / ratio, center_frequency * ratio)
+}
+
+/// The band edges of a band shelf, with the upper one kept below Nyquist, where the shelf at
+/// it would get unstable
+pub(crate) fn bandshelf_edges<F: utils::Float>(
+    center_frequency: F,
+    q: F,
+    sample_rate: F,
+) -> (F, F) {
+    let (lower_edge, upper_edge) = band_edges(center_frequency, q);
+    (
+        lower_edge,
+        upper_edge.min(F::from(0.49).unwrap() * sample_rate),
+    )
+}
+
+/// Holds the states of MAX_NUM_SECTIONS sections without allocating. Longer cascades (e.g. from
+/// pole_zero::PoleZero) let it grow on the first call to process.
+#[derive(Debug)]
+pub struct State<F: utils::Float> {
+    section_states: SectionsOf<filter::State<F>>,
+}
+
+impl<F: utils::Float> State<F> {
+    pub fn new() -> Self {
+        Self {
+            section_states: (0..MAX_NUM_SECTIONS)
+                .map(|_| filter::State::new())
+                .collect(),
+        }
+    }
+
+    pub fn process(&mut self, coefficients: &Coefficients<F>, sample: F) -> F {
+        if self.section_states.len() < coefficients.sections.len() {
+            self.section_states
+                .resize_with(coefficients.sections.len(), filter::State::new);
+        }
+        let mut output = sample;
+        for (state, section) in self
+            .section_states
+            .iter_mut()
+            .zip(coefficients.sections.iter())
+        {
+            output = state.process(section, output);
+        }
+        output
+    }
+
+    /// Same as processing each sample, but section by section over the whole block
+    pub fn process_block(&mut self, coefficients: &Coefficients<F>, samples: &mut [F]) {
+        if self.section_states.len() < coefficients.sections.len() {
+            self.section_states
+                .resize_with(coefficients.sections.len(), filter::State::new);
+        }
+        for (state, section) in self
+            .section_states
+            .iter_mut()
+            .zip(coefficients.sections.iter())
+        {
+            state.process_block(section, samples);
+        }
+    }
+
+    pub fn process_block_into(
+        &mut self,
+        coefficients: &Coefficients<F>,
+        input: &[F],
+        output: &mut [F],
+    ) {
+        output.copy_from_slice(input);
+        self.process_block(coefficients, output);
+    }
+
+    pub fn reset(&mut self) {
+        for state in self.section_states.iter_mut() {
+            state.reset();
+        }
+    }
+}
+
+impl<F: utils::Float> Default for State<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct Filter<F: utils::Float> {
+    coefficients: Coefficients<F>,
+    state: State<F>,
+}
+
+impl<F: utils::Float> Filter<F> {
+    pub fn new(coefficients: Coefficients<F>) -> Self {
+        Self {
+            coefficients,
+            state: State::new(),
+        }
+    }
+
+    pub fn set_coefficients(&mut self, coefficients: Coefficients<F>, reset_state: bool) {
+        self.coefficients = coefficients;
+        if reset_state {
+            self.reset_state();
+        }
+    }
+
+    pub fn process(&mut self, sample: F) -> F {
+        self.state.process(&self.coefficients, sample)
+    }
+
+    pub fn process_block(&mut self, samples: &mut [F]) {
+        self.state.process_block(&self.coefficients, samples);
+    }
+
+    pub fn process_block_into(&mut self, input: &[F], output: &mut [F]) {
+        self.state
+            .process_block_into(&self.coefficients, input, output);
+    }
+
+    pub fn reset_state(&mut self) {
+        self.state.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::utils as biquad_utils;
+    use crate::utils::amplitude_to_db;
+    use assert_approx_eq::assert_approx_eq;
+    use num::complex::ComplexFloat;
+
+    const SQRT_HALF: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn number_of_sections() {
+        let count = |slope, alignment| {
+            Coefficients::from_lowpass(1000.0, SQRT_HALF, slope, alignment, 48000.0)
+                .sections()
+                .len()
+        };
+        assert_eq!(count(eq::Slope::Db6, eq::Alignment::Butterworth), 1);
+        assert_eq!(count(eq::Slope::Db18, eq::Alignment::Butterworth), 2);
+        assert_eq!(count(eq::Slope::Db48, eq::Alignment::Butterworth), 4);
+        assert_eq!(count(eq::Slope::Db12, eq::Alignment::LinkwitzRiley), 2);
+        // odd orders fall back to Butterworth, see eq::Alignment
+        assert_eq!(count(eq::Slope::Db18, eq::Alignment::LinkwitzRiley), 2);
+        assert_eq!(count(eq::Slope::Db36, eq::Alignment::LinkwitzRiley), 4);
+        assert_eq!(count(eq::Slope::Db48, eq::Alignment::LinkwitzRiley), 4);
+    }
+
+    #[test]
+    fn linkwitz_riley_resonates_once() {
+        let section_qs = |alignment| pass_sections(eq::Slope::Db24, alignment, 2.0, || 0.0, |q| q);
+        assert_approx_eq!(section_qs(eq::Alignment::LinkwitzRiley)[0], 2.0);
+        // the other squared Butterworth is not resonant
+        assert_approx_eq!(section_qs(eq::Alignment::LinkwitzRiley)[1], SQRT_HALF);
+    }
+
+    #[test]
+    fn butterworth_12db_is_cookbook_lowpass() {
+        let sample_rate = 44100.0;
+        let q = 2.3;
+        let cascade = Coefficients::from_lowpass(
+            800.0,
+            q,
+            eq::Slope::Db12,
+            eq::Alignment::Butterworth,
+            sample_rate,
+        );
+        let cookbook = coefficients::Coefficients::from_lowpass(800.0, q, sample_rate);
+        let section = &cascade.sections()[0];
+        assert_approx_eq!(section.a1, cookbook.a1);
+        assert_approx_eq!(section.a2, cookbook.a2);
+        assert_approx_eq!(section.b0, cookbook.b0);
+        assert_approx_eq!(section.b1, cookbook.b1);
+        assert_approx_eq!(section.b2, cookbook.b2);
+    }
+
+    #[test]
+    fn validate_butterworth_slopes() {
+        let sample_rate = 96000.0;
+        let cutoff_frequency = 500.0;
+        for slope in eq::Slope::ALL {
+            let lowpass = biquad_utils::cascade::make_frequency_response(
+                Coefficients::from_lowpass(
+                    cutoff_frequency,
+                    SQRT_HALF,
+                    *slope,
+                    eq::Alignment::Butterworth,
+                    sample_rate,
+                ),
+                sample_rate,
+            );
+            let highpass = biquad_utils::cascade::make_frequency_response(
+                Coefficients::from_highpass(
+                    cutoff_frequency,
+                    SQRT_HALF,
+                    *slope,
+                    eq::Alignment::Butterworth,
+                    sample_rate,
+                ),
+                sample_rate,
+            );
+
+            assert_approx_eq!(
+                amplitude_to_db(lowpass(cutoff_frequency).abs()),
+                -3.0103,
+                1e-3
+            );
+            assert_approx_eq!(
+                amplitude_to_db(highpass(cutoff_frequency).abs()),
+                -3.0103,
+                1e-3
+            );
+            assert_approx_eq!(amplitude_to_db(lowpass(5.0).abs()), 0.0, 1e-2);
+            assert_approx_eq!(amplitude_to_db(highpass(20000.0).abs()), 0.0, 1e-2);
+
+            // two octaves away from the cutoff, we should be close to the asymptotic slope
+            let expected_db = -6.0206 * 2.0 * slope.order() as f64;
+            let lowpass_db = amplitude_to_db(lowpass(4.0 * cutoff_frequency).abs());
+            let highpass_db = amplitude_to_db(highpass(0.25 * cutoff_frequency).abs());
+            assert_approx_eq!(lowpass_db, expected_db, 0.5);
+            assert_approx_eq!(highpass_db, expected_db, 0.5);
+        }
+    }
+
+    #[test]
+    fn linkwitz_riley_sums_flat() {
+        let sample_rate = 48000.0;
+        let cutoff_frequency = 2000.0;
+        for slope in [eq::Slope::Db24, eq::Slope::Db48] {
+            let lowpass = biquad_utils::cascade::make_frequency_response(
+                Coefficients::from_lowpass(
+                    cutoff_frequency,
+                    SQRT_HALF,
+                    slope,
+                    eq::Alignment::LinkwitzRiley,
+                    sample_rate,
+                ),
+                sample_rate,
+            );
+            let highpass = biquad_utils::cascade::make_frequency_response(
+                Coefficients::from_highpass(
+                    cutoff_frequency,
+                    SQRT_HALF,
+                    slope,
+                    eq::Alignment::LinkwitzRiley,
+                    sample_rate,
+                ),
+                sample_rate,
+            );
+
+            assert_approx_eq!(
+                amplitude_to_db(lowpass(cutoff_frequency).abs()),
+                -6.0206,
+                1e-3
+            );
+            for i in 1..100 {
+                let frequency = (i * 200) as f64;
+                let sum = lowpass(frequency) + highpass(frequency);
+                assert_approx_eq!(sum.abs(), 1.0, 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn validate_tilt() {
+        let sample_rate = 48000.0;
+        let pivot_frequency = 800.0;
+        let gain_db = 4.5;
+        let single_section = biquad_utils::make_frequency_response(
+            coefficients::Coefficients::from_tilt_db(gain_db, pivot_frequency, sample_rate),
+            sample_rate,
+        );
+        let shelf_pair = biquad_utils::cascade::make_frequency_response(
+            Coefficients::from_tilt_db(gain_db, pivot_frequency, sample_rate),
+            sample_rate,
+        );
+
+        for response in [&single_section as &dyn Fn(f64) -> _, &shelf_pair] {
+            assert_approx_eq!(amplitude_to_db(response(0.0).abs()), -gain_db);
+            assert_approx_eq!(amplitude_to_db(response(pivot_frequency).abs()), 0.0);
+            assert_approx_eq!(amplitude_to_db(response(24000.0).abs()), gain_db);
+        }
+    }
+
+    #[test]
+    fn validate_bandshelf() {
+        let sample_rate = 48000.0;
+        let frequency = 1000.0;
+        let q = 0.2;
+        let gain_db = -9.0;
+        let (lower_edge, upper_edge) = band_edges(frequency, q);
+        assert_approx_eq!(upper_edge - lower_edge, frequency / q);
+        assert_approx_eq!(lower_edge * upper_edge, frequency * frequency);
+
+        let response = biquad_utils::cascade::make_frequency_response(
+            Coefficients::from_bandshelf_db(gain_db, frequency, q, sample_rate),
+            sample_rate,
+        );
+        let response_db = |f: f64| amplitude_to_db(response(f).abs());
+        assert_approx_eq!(response_db(frequency), gain_db, 0.2);
+        assert_approx_eq!(response_db(lower_edge), 0.5 * gain_db, 0.2);
+        assert_approx_eq!(response_db(upper_edge), 0.5 * gain_db, 0.2);
+        assert_approx_eq!(response_db(1.0), 0.0, 1e-3);
+        assert_approx_eq!(response_db(24000.0), 0.0, 0.2);
+    }
+
+    #[test]
+    fn wide_bandshelf_near_nyquist_in_all_designs() {
+        let sample_rate = 44100.0;
+        for design in eq::Design::ALL {
+            let eq = eq::Eq {
+                gain: eq::Gain::Db(6.0),
+                frequency: eq::Frequency::Hz(12000.0),
+                bandwidth: eq::Bandwidth::Q(0.3),
+                eq_type: eq::EqType::BandShelf,
+                slope: eq::Slope::default(),
+                alignment: eq::Alignment::default(),
+                design: *design,
+                peak_shape: eq::PeakShape::default(),
+            };
+            let coefficients = Coefficients::try_from_eq(&eq, sample_rate).unwrap();
+            let response =
+                biquad_utils::cascade::make_frequency_response(coefficients, sample_rate);
+            assert_approx_eq!(amplitude_to_db(response(20.0).abs()), 0.0, 0.5);
+            assert_approx_eq!(amplitude_to_db(response(12000.0).abs()), 6.0, 1.0);
+        }
+    }
+
+    #[test]
+    fn filter_matches_section_filters() {
+        let sample_rate = 48000.0;
+        let coefficients = Coefficients::from_highpass(
+            300.0,
+            1.2,
+            eq::Slope::Db36,
+            eq::Alignment::Butterworth,
+            sample_rate,
+        );
+        let mut cascade_filter = Filter::new(coefficients.clone());
+        let mut section_filters = coefficients
+            .sections()
+            .iter()
+            .map(|section| filter::Filter::new(section.clone()))
+            .collect::<Vec<_>>();
+        for i in 0..200 {
+            let sample = ((i * 7) % 13) as f64 / 13.0 - 0.5;
+            assert_approx_eq!(
+                cascade_filter.process(sample),
+                biquad_utils::process_sequential(&mut section_filters, sample)
+            );
+        }
+    }
+
+    #[test]
+    fn block_processing_matches_sample_processing() {
+        let sample_rate = 48000.0;
+        let coefficients = Coefficients::from_lowpass(
+            2000.0,
+            0.9,
+            eq::Slope::Db48,
+            eq::Alignment::LinkwitzRiley,
+            sample_rate,
+        );
+        let input = (0..300)
+            .map(|i| ((i * 5) % 11) as f64 / 11.0 - 0.5)
+            .collect::<Vec<_>>();
+
+        let mut sample_filter = Filter::new(coefficients.clone());
+        let expected = input
+            .iter()
+            .map(|sample| sample_filter.process(*sample))
+            .collect::<Vec<_>>();
+
+        let mut block_filter = Filter::new(coefficients.clone());
+        let mut output = vec![0.0; input.len()];
+        // blocks of different lengths, the states carry over
+        block_filter.process_block_into(&input[..100], &mut output[..100]);
+        output[100..].copy_from_slice(&input[100..]);
+        block_filter.process_block(&mut output[100..257]);
+        block_filter.process_block(&mut output[257..]);
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-12);
+        }
+
+        let mut section_filters = coefficients
+            .sections()
+            .iter()
+            .map(|section| filter::Filter::new(section.clone()))
+            .collect::<Vec<_>>();
+        let mut sequential_output = input.clone();
+        biquad_utils::process_sequential_block(&mut section_filters, &mut sequential_output);
+        for (actual, expected) in sequential_output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-12);
+        }
+    }
+}
@@ -1,6 +1,11 @@
+use crate::biquad::analog;
+use crate::biquad::utils as biquad_utils;
 use crate::eq;
 use crate::utils;
 
+/// Number of frequencies the numerator of the analog matched design is fitted at
+const MATCHED_FIT_POINTS: usize = 64;
+
 #[derive(Debug, Clone)]
 pub struct Coefficients<F: utils::Float> {
     pub a1: F,
@@ -10,25 +15,185 @@ pub struct Coefficients<F: utils::Float> {
     pub b2: F,
 }
 
+/// Reasons why no usable coefficients can be designed for an eq, see Coefficients::try_from_eq
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error<F: utils::Float> {
+    FrequencyAboveNyquist {
+        frequency: F,
+        nyquist_frequency: F,
+    },
+    NonPositiveFrequency {
+        frequency: F,
+    },
+    NonPositiveQ {
+        q: F,
+    },
+    NonFiniteGain {
+        gain_db: F,
+    },
+    /// The parameters are in range, but the design has poles on or outside the unit circle
+    /// (or isn't finite)
+    Unstable,
+}
+
+impl<F: utils::Float> std::fmt::Display for Error<F> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let to_f64 = |value: &F| value.to_f64().unwrap();
+        match self {
+            Self::FrequencyAboveNyquist {
+                frequency,
+                nyquist_frequency,
+            } => write!(
+                formatter,
+                "frequency {:.0} Hz is not below Nyquist ({:.0} Hz)",
+                to_f64(frequency),
+                to_f64(nyquist_frequency)
+            ),
+            Self::NonPositiveFrequency { frequency } => {
+                write!(
+                    formatter,
+                    "frequency {} Hz is not positive",
+                    to_f64(frequency)
+                )
+            }
+            Self::NonPositiveQ { q } => write!(formatter, "q {} is not positive", to_f64(q)),
+            Self::NonFiniteGain { gain_db } => {
+                write!(formatter, "gain {} dB is not finite", to_f64(gain_db))
+            }
+            Self::Unstable => write!(formatter, "filter is unstable"),
+        }
+    }
+}
+
+impl<F: utils::Float + std::fmt::Debug> std::error::Error for Error<F> {}
+
+impl<F: utils::Float> Error<F> {
+    pub fn cast<G: utils::Float>(&self) -> Error<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        match *self {
+            Error::FrequencyAboveNyquist {
+                frequency,
+                nyquist_frequency,
+            } => Error::FrequencyAboveNyquist {
+                frequency: cast(frequency),
+                nyquist_frequency: cast(nyquist_frequency),
+            },
+            Error::NonPositiveFrequency { frequency } => Error::NonPositiveFrequency {
+                frequency: cast(frequency),
+            },
+            Error::NonPositiveQ { q } => Error::NonPositiveQ { q: cast(q) },
+            Error::NonFiniteGain { gain_db } => Error::NonFiniteGain {
+                gain_db: cast(gain_db),
+            },
+            Error::Unstable => Error::Unstable,
+        }
+    }
+}
+
+/// Checks the parameters of the eq that are used by its type, such that the designs can't
+/// fail their debug asserts or produce non-finite coefficients
+pub fn validate_eq<F: utils::Float>(eq: &eq::Eq<F>, sample_rate: F) -> Result<(), Error<F>> {
+    if eq.eq_type.has_frequency() {
+        let frequency = eq.frequency.hz();
+        if frequency.is_nan() || frequency <= F::ZERO {
+            return Err(Error::NonPositiveFrequency { frequency });
+        }
+        let nyquist_frequency = F::ONE_HALF * sample_rate;
+        if frequency >= nyquist_frequency {
+            return Err(Error::FrequencyAboveNyquist {
+                frequency,
+                nyquist_frequency,
+            });
+        }
+    }
+    if eq.eq_type.has_q() {
+        let q = eq.q();
+        if q <= F::ZERO || !q.is_finite() {
+            return Err(Error::NonPositiveQ { q });
+        }
+    }
+    if eq.eq_type.has_gain_db() {
+        let gain_db = eq.gain.db();
+        if !gain_db.is_finite() {
+            return Err(Error::NonFiniteGain { gain_db });
+        }
+    }
+    Ok(())
+}
+
 /// Formulas for coefficients taken from here http://shepazu.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
 impl<F: utils::Float> Coefficients<F> {
+    /// Eq types that need more than one section (see cascade::Coefficients::from_eq) are
+    /// approximated by a single section here.
     pub fn from_eq(eq: &eq::Eq<F>, sample_rate: F) -> Self {
+        if eq.design != eq::Design::Cookbook {
+            return Self::from_analog(&analog::AnalogBiquad::from_eq(eq), eq.design, sample_rate);
+        }
         let gain_db = eq.gain.db();
         let frequency = eq.frequency.hz();
         match eq.eq_type {
             eq::EqType::Volume => Self::from_volume_db(gain_db),
-            eq::EqType::LowPass => Self::from_lowpass(frequency, eq.q, sample_rate),
-            eq::EqType::HighPass => Self::from_highpass(frequency, eq.q, sample_rate),
-            eq::EqType::BandPass => Self::from_bandpass(frequency, eq.q, sample_rate),
-            eq::EqType::AllPass => Self::from_allpass(frequency, eq.q, sample_rate),
-            eq::EqType::Notch => Self::from_notch(frequency, eq.q, sample_rate),
-            eq::EqType::Peak => Self::from_peak_db(gain_db, frequency, eq.q, sample_rate),
-            eq::EqType::LowShelf => Self::from_lowshelf_db(gain_db, frequency, eq.q, sample_rate),
-            eq::EqType::HighShelf => Self::from_highshelf_db(gain_db, frequency, eq.q, sample_rate),
+            eq::EqType::LowPass => Self::from_lowpass(frequency, eq.q(), sample_rate),
+            eq::EqType::HighPass => Self::from_highpass(frequency, eq.q(), sample_rate),
+            eq::EqType::BandPass => Self::from_bandpass(frequency, eq.q(), sample_rate),
+            eq::EqType::AllPass => Self::from_allpass(frequency, eq.q(), sample_rate),
+            eq::EqType::Notch => Self::from_notch(frequency, eq.q(), sample_rate),
+            eq::EqType::Peak => Self::from_peak_db(
+                gain_db,
+                frequency,
+                eq.peak_shape.cookbook_q(gain_db, eq.q()),
+                sample_rate,
+            ),
+            eq::EqType::LowShelf => Self::from_lowshelf_db(gain_db, frequency, eq.q(), sample_rate),
+            eq::EqType::HighShelf => {
+                Self::from_highshelf_db(gain_db, frequency, eq.q(), sample_rate)
+            }
+            eq::EqType::Tilt => Self::from_tilt_db(gain_db, frequency, sample_rate),
+            eq::EqType::BandShelf => Self::from_peak_db(gain_db, frequency, eq.q(), sample_rate),
+            eq::EqType::FirstOrderLowPass => Self::from_first_order_lowpass(frequency, sample_rate),
+            eq::EqType::FirstOrderHighPass => {
+                Self::from_first_order_highpass(frequency, sample_rate)
+            }
+            eq::EqType::FirstOrderAllPass => Self::from_first_order_allpass(frequency, sample_rate),
+            eq::EqType::FirstOrderLowShelf => {
+                Self::from_first_order_lowshelf_db(gain_db, frequency, sample_rate)
+            }
+            eq::EqType::FirstOrderHighShelf => {
+                Self::from_first_order_highshelf_db(gain_db, frequency, sample_rate)
+            }
             eq::EqType::Bypassed => Self::passthrough(),
         }
     }
 
+    /// Like from_eq, but validates the eq first (see validate_eq) and checks that the result is
+    /// stable
+    pub fn try_from_eq(eq: &eq::Eq<F>, sample_rate: F) -> Result<Self, Error<F>> {
+        validate_eq(eq, sample_rate)?;
+        let coefficients = Self::from_eq(eq, sample_rate);
+        if coefficients.is_finite() && biquad_utils::is_stable(&coefficients) {
+            Ok(coefficients)
+        } else {
+            Err(Error::Unstable)
+        }
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Coefficients<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        Coefficients {
+            a1: cast(self.a1),
+            a2: cast(self.a2),
+            b0: cast(self.b0),
+            b1: cast(self.b1),
+            b2: cast(self.b2),
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.a1, self.a2, self.b0, self.b1, self.b2]
+            .iter()
+            .all(|coefficient| coefficient.is_finite())
+    }
+
     pub const fn muted() -> Self {
         Self::from_volume_linear(F::ZERO)
     }
@@ -56,7 +221,7 @@ impl<F: utils::Float> Coefficients<F> {
         let one_minus_cos_omega0 = F::ONE - cos_omega0;
         let half_one_minus_cos_omega0 = F::ONE_HALF * one_minus_cos_omega0;
         let a0 = F::ONE + alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
         Self {
             a1: -one_through_a0 * F::TWO * cos_omega0,
@@ -72,7 +237,7 @@ impl<F: utils::Float> Coefficients<F> {
         let one_plus_cos_omega0 = F::ONE + cos_omega0;
         let half_one_plus_cos_omega0 = F::ONE_HALF * one_plus_cos_omega0;
         let a0 = F::ONE + alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
         Self {
             a1: -one_through_a0 * F::TWO * cos_omega0,
@@ -83,10 +248,114 @@ impl<F: utils::Float> Coefficients<F> {
         }
     }
 
+    /// First order section (b2 = a2 = 0), bilinear transform prewarped at the cutoff frequency
+    pub fn from_first_order_lowpass(cutoff_frequency: F, sample_rate: F) -> Self {
+        let k = Self::tan_half_omega0(cutoff_frequency, sample_rate);
+        let a0 = k + F::ONE;
+        debug_assert!(a0 != F::ZERO);
+        let one_through_a0 = F::ONE / a0;
+        Self {
+            a1: one_through_a0 * (k - F::ONE),
+            a2: F::ZERO,
+            b0: one_through_a0 * k,
+            b1: one_through_a0 * k,
+            b2: F::ZERO,
+        }
+    }
+
+    /// First order section (b2 = a2 = 0), bilinear transform prewarped at the cutoff frequency
+    pub fn from_first_order_highpass(cutoff_frequency: F, sample_rate: F) -> Self {
+        let k = Self::tan_half_omega0(cutoff_frequency, sample_rate);
+        let a0 = k + F::ONE;
+        debug_assert!(a0 != F::ZERO);
+        let one_through_a0 = F::ONE / a0;
+        Self {
+            a1: one_through_a0 * (k - F::ONE),
+            a2: F::ZERO,
+            b0: one_through_a0,
+            b1: -one_through_a0,
+            b2: F::ZERO,
+        }
+    }
+
+    /// First order section (b2 = a2 = 0) with 0 phase at DC and -pi/2 at the given frequency
+    pub fn from_first_order_allpass(frequency: F, sample_rate: F) -> Self {
+        let k = Self::tan_half_omega0(frequency, sample_rate);
+        let a0 = k + F::ONE;
+        debug_assert!(a0 != F::ZERO);
+        let one_through_a0 = F::ONE / a0;
+        Self {
+            a1: one_through_a0 * (k - F::ONE),
+            a2: F::ZERO,
+            b0: one_through_a0 * (k - F::ONE),
+            b1: F::ONE,
+            b2: F::ZERO,
+        }
+    }
+
+    /// First order section (b2 = a2 = 0), 6 dB/oct, with half of the gain (in dB) reached at the cutoff frequency
+    pub fn from_first_order_lowshelf_linear(
+        gain_linear: F,
+        cutoff_frequency: F,
+        sample_rate: F,
+    ) -> Self {
+        let sqrt_gain = F::sqrt(gain_linear);
+        let k = Self::tan_half_omega0(cutoff_frequency, sample_rate);
+        let k_times_sqrt_gain = k * sqrt_gain;
+        let k_through_sqrt_gain = k / sqrt_gain;
+        let a0 = F::ONE + k_through_sqrt_gain;
+        debug_assert!(a0 != F::ZERO);
+        let one_through_a0 = F::ONE / a0;
+        Self {
+            a1: one_through_a0 * (k_through_sqrt_gain - F::ONE),
+            a2: F::ZERO,
+            b0: one_through_a0 * (F::ONE + k_times_sqrt_gain),
+            b1: one_through_a0 * (k_times_sqrt_gain - F::ONE),
+            b2: F::ZERO,
+        }
+    }
+
+    pub fn from_first_order_lowshelf_db(gain_db: F, cutoff_frequency: F, sample_rate: F) -> Self {
+        Self::from_first_order_lowshelf_linear(
+            utils::db_to_amplitude(gain_db),
+            cutoff_frequency,
+            sample_rate,
+        )
+    }
+
+    /// First order section (b2 = a2 = 0), 6 dB/oct, with half of the gain (in dB) reached at the cutoff frequency
+    pub fn from_first_order_highshelf_linear(
+        gain_linear: F,
+        cutoff_frequency: F,
+        sample_rate: F,
+    ) -> Self {
+        let sqrt_gain = F::sqrt(gain_linear);
+        let k = Self::tan_half_omega0(cutoff_frequency, sample_rate);
+        let k_times_sqrt_gain = k * sqrt_gain;
+        let a0 = F::ONE + k_times_sqrt_gain;
+        debug_assert!(a0 != F::ZERO);
+        let one_through_a0 = F::ONE / a0;
+        Self {
+            a1: one_through_a0 * (k_times_sqrt_gain - F::ONE),
+            a2: F::ZERO,
+            b0: one_through_a0 * (gain_linear + k_times_sqrt_gain),
+            b1: one_through_a0 * (k_times_sqrt_gain - gain_linear),
+            b2: F::ZERO,
+        }
+    }
+
+    pub fn from_first_order_highshelf_db(gain_db: F, cutoff_frequency: F, sample_rate: F) -> Self {
+        Self::from_first_order_highshelf_linear(
+            utils::db_to_amplitude(gain_db),
+            cutoff_frequency,
+            sample_rate,
+        )
+    }
+
     pub fn from_bandpass(frequency: F, q: F, sample_rate: F) -> Self {
         let (alpha, cos_omega0) = Self::alpha_and_cos_omega0(frequency, q, sample_rate);
         let a0 = F::ONE + alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
         Self {
             a1: -one_through_a0 * F::TWO * cos_omega0,
@@ -103,7 +372,7 @@ impl<F: utils::Float> Coefficients<F> {
         let one_plus_alpha = F::ONE + alpha;
         let one_minus_alpha = F::ONE - alpha;
         let a0 = one_plus_alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
         Self {
             b0: one_through_a0 * one_minus_alpha,
@@ -118,7 +387,7 @@ impl<F: utils::Float> Coefficients<F> {
         let (alpha, cos_omega0) = Self::alpha_and_cos_omega0(frequency, q, sample_rate);
         let two_times_cos_omega0 = F::TWO * cos_omega0;
         let a0 = F::ONE + alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
 
         Self {
@@ -137,7 +406,7 @@ impl<F: utils::Float> Coefficients<F> {
         let alpha_times_a = alpha * a;
         let alpha_through_a = alpha / a;
         let a0 = F::ONE + alpha_through_a;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
         Self {
             b0: one_through_a0 * (F::ONE + alpha_times_a),
@@ -160,7 +429,7 @@ impl<F: utils::Float> Coefficients<F> {
         let a_minus_one = a - F::ONE;
         let two_times_sqrt_a_times_alpha = F::TWO * sqrt_a * alpha;
         let a0 = a_plus_one + a_minus_one * cos_omega0 + two_times_sqrt_a_times_alpha;
-        assert!(a0 != F::ZERO);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
 
         Self {
@@ -199,7 +468,7 @@ impl<F: utils::Float> Coefficients<F> {
         let a_minus_one = a - F::ONE;
         let two_times_sqrt_a_times_alpha = F::TWO * sqrt_a * alpha;
         let a0 = a_plus_one - a_minus_one * cos_omega0 + two_times_sqrt_a_times_alpha;
-        assert!(a0 != F::ONE);
+        debug_assert!(a0 != F::ZERO);
         let one_through_a0 = F::ONE / a0;
 
         Self {
@@ -225,9 +494,453 @@ impl<F: utils::Float> Coefficients<F> {
         )
     }
 
+    /// Single section tilt: high shelf with twice the gain, scaled down by the gain,
+    /// such that the low end gets -gain_db, the high end +gain_db, and the pivot stays at 0 dB.
+    pub fn from_tilt_db(gain_db: F, pivot_frequency: F, sample_rate: F) -> Self {
+        let mut coefficients = Self::from_highshelf_db(
+            F::TWO * gain_db,
+            pivot_frequency,
+            F::FRAC_1_SQRT_2(),
+            sample_rate,
+        );
+        let scale = utils::db_to_amplitude(-gain_db);
+        coefficients.b0 *= scale;
+        coefficients.b1 *= scale;
+        coefficients.b2 *= scale;
+        coefficients
+    }
+
+    /// Discretises the analog prototype with the given design, Cookbook with the bilinear
+    /// transform.
+    pub fn from_analog(
+        analog: &analog::AnalogBiquad<F>,
+        design: eq::Design,
+        sample_rate: F,
+    ) -> Self {
+        match design {
+            eq::Design::Cookbook => Self::from_analog_bilinear(analog, sample_rate),
+            eq::Design::AnalogMatched => Self::from_analog_matched(analog, sample_rate),
+            eq::Design::MatchedZ => Self::from_analog_matched_z(analog, sample_rate),
+            eq::Design::ImpulseInvariant => {
+                Self::from_analog_impulse_invariant(analog, sample_rate)
+            }
+        }
+    }
+
+    /// Bilinear transform s = (1 - z^-1) / (1 + z^-1) / tan(omega0/2), prewarped such that the
+    /// frequency of the analog prototype is mapped exactly. This is how the cookbook formulas
+    /// are derived.
+    pub fn from_analog_bilinear(analog: &analog::AnalogBiquad<F>, sample_rate: F) -> Self {
+        if analog.is_zeroth_order() {
+            return Self::from_volume_linear(analog.b0 / analog.a0);
+        }
+        let c = F::ONE / Self::tan_half_omega0(analog.frequency, sample_rate);
+        let transform = |p0: F, p1: F, p2: F| {
+            if analog.is_first_order() {
+                [p0 + p1 * c, p0 - p1 * c, F::ZERO]
+            } else {
+                let p2_c_squared = p2 * c * c;
+                [
+                    p0 + p1 * c + p2_c_squared,
+                    F::TWO * (p0 - p2_c_squared),
+                    p0 - p1 * c + p2_c_squared,
+                ]
+            }
+        };
+        let b = transform(analog.b0, analog.b1, analog.b2);
+        let a = transform(analog.a0, analog.a1, analog.a2);
+        debug_assert!(a[0] != F::ZERO);
+        let one_through_a0 = F::ONE / a[0];
+        Self {
+            a1: one_through_a0 * a[1],
+            a2: one_through_a0 * a[2],
+            b0: one_through_a0 * b[0],
+            b1: one_through_a0 * b[1],
+            b2: one_through_a0 * b[2],
+        }
+    }
+
+    /// Matched z transform: poles and zeros are mapped with z = exp(s*T). Zeros at infinity
+    /// (e.g. of a low pass) are placed at Nyquist. The gain is matched at DC, or at the
+    /// frequency of the prototype (at most 0.9 * Nyquist), if the prototype blocks DC.
+    pub fn from_analog_matched_z(analog: &analog::AnalogBiquad<F>, sample_rate: F) -> Self {
+        Self::design_in_f64(analog, sample_rate, matched_z_f64)
+    }
+
+    /// Impulse invariant transform: the digital impulse response is the sampled analog impulse
+    /// response, scaled with T. The direct feed through of prototypes with as many zeros as
+    /// poles is added to the first sample, and half of the jump at t = 0 is removed (Jackson,
+    /// "A correction to impulse-invariant transforms", 2000). Everything above Nyquist aliases,
+    /// so expect errors for prototypes with much gain at high frequencies.
+    pub fn from_analog_impulse_invariant(analog: &analog::AnalogBiquad<F>, sample_rate: F) -> Self {
+        Self::design_in_f64(analog, sample_rate, impulse_invariant_f64)
+    }
+
+    /// Analog matched design after Vicanek, "Matched Second Order Digital Filters" (2016).
+    /// The poles are mapped exactly with z = exp(s*T), which avoids the frequency warping
+    /// of the bilinear transform. The zeros are then chosen to match the analog magnitude
+    /// response, using the representation
+    /// |H|^2 = (B0*phi0 + B1*phi1 + B2*phi2) / (A0*phi0 + A1*phi1 + A2*phi2)
+    /// with phi1 = sin^2(omega/2), phi0 = 1 - phi1 and phi2 = 4*phi0*phi1:
+    /// B0 matches DC exactly, B1 and B2 are a least squares fit of the relative error on a
+    /// log frequency grid up to Nyquist. Zeros on the imaginary axis (notch), double zeros at
+    /// DC (high pass) and all passes keep their structure instead.
+    /// The design is done in f64, as the fit is badly conditioned in f32.
+    pub fn from_analog_matched(analog: &analog::AnalogBiquad<F>, sample_rate: F) -> Self {
+        Self::design_in_f64(analog, sample_rate, matched_f64)
+    }
+
+    fn design_in_f64(
+        analog: &analog::AnalogBiquad<F>,
+        sample_rate: F,
+        design: fn(&analog::AnalogBiquad<f64>, f64) -> Coefficients<f64>,
+    ) -> Self {
+        let to_f64 = |value: F| value.to_f64().unwrap();
+        let analog = analog::AnalogBiquad {
+            frequency: to_f64(analog.frequency),
+            b0: to_f64(analog.b0),
+            b1: to_f64(analog.b1),
+            b2: to_f64(analog.b2),
+            a0: to_f64(analog.a0),
+            a1: to_f64(analog.a1),
+            a2: to_f64(analog.a2),
+        };
+        let coefficients = design(&analog, to_f64(sample_rate));
+        let from_f64 = |value: f64| F::from(value).unwrap();
+        Self {
+            a1: from_f64(coefficients.a1),
+            a2: from_f64(coefficients.a2),
+            b0: from_f64(coefficients.b0),
+            b1: from_f64(coefficients.b1),
+            b2: from_f64(coefficients.b2),
+        }
+    }
+
     fn alpha_and_cos_omega0(frequency: F, q: F, sample_rate: F) -> (F, F) {
         let omega0 = F::TWO_PI * frequency / sample_rate;
         let alpha = F::ONE_HALF * F::sin(omega0) / q;
         return (alpha, F::cos(omega0));
     }
+
+    fn tan_half_omega0(frequency: F, sample_rate: F) -> F {
+        F::tan(F::ONE_HALF * utils::omega(frequency, sample_rate))
+    }
+}
+
+fn matched_f64(analog: &analog::AnalogBiquad<f64>, sample_rate: f64) -> Coefficients<f64> {
+    let omega0 = utils::omega(analog.frequency, sample_rate);
+    let (a1, a2) = matched_roots([analog.a0, analog.a1, analog.a2], omega0);
+
+    if analog.is_allpass() {
+        return if analog.is_first_order() {
+            Coefficients {
+                a1,
+                a2,
+                b0: a1,
+                b1: 1.0,
+                b2: 0.0,
+            }
+        } else {
+            Coefficients {
+                a1,
+                a2,
+                b0: a2,
+                b1: a1,
+                b2: 1.0,
+            }
+        };
+    }
+
+    let denominator_a0 = (1.0 + a1 + a2).powi(2);
+    let denominator_a1 = (1.0 - a1 + a2).powi(2);
+    let denominator_a2 = -4.0 * a2;
+    let denominator = |phi1: f64| {
+        let phi0 = 1.0 - phi1;
+        denominator_a0 * phi0 + denominator_a1 * phi1 + denominator_a2 * 4.0 * phi0 * phi1
+    };
+    let analog_squared_magnitude = |omega: f64| {
+        analog
+            .frequency_response(omega / std::f64::consts::TAU * sample_rate)
+            .norm_sqr()
+    };
+    let phi1 = |omega: f64| (0.5 * omega).sin().powi(2);
+
+    let numerator_b0 = analog_squared_magnitude(0.0) * denominator_a0;
+    if analog.is_first_order() {
+        let numerator_b1 = analog_squared_magnitude(std::f64::consts::PI) * denominator_a1;
+        let (sqrt_b0, sqrt_b1) = (numerator_b0.sqrt(), numerator_b1.sqrt());
+        return Coefficients {
+            a1,
+            a2,
+            b0: 0.5 * (sqrt_b0 + sqrt_b1),
+            b1: 0.5 * (sqrt_b0 - sqrt_b1),
+            b2: 0.0,
+        };
+    }
+
+    // frequency to match at, if the analog prototype has its characteristic frequency
+    // above Nyquist, we match a bit below Nyquist instead
+    let match_omega = omega0.min(0.9 * std::f64::consts::PI);
+    if analog.b0 == 0.0 && analog.b1 == 0.0 {
+        let match_phi1 = phi1(match_omega);
+        let gain = (analog_squared_magnitude(match_omega) * denominator(match_phi1)).sqrt()
+            / (4.0 * match_phi1);
+        return Coefficients {
+            a1,
+            a2,
+            b0: gain,
+            b1: -2.0 * gain,
+            b2: gain,
+        };
+    }
+    if analog.b1 == 0.0 && analog.b0 > 0.0 && analog.b2 > 0.0 {
+        let zero_omega = (analog.b0 / analog.b2).sqrt() * omega0;
+        if zero_omega < std::f64::consts::PI {
+            let cos_zero_omega = zero_omega.cos();
+            let gain = analog_squared_magnitude(0.0).sqrt() * (1.0 + a1 + a2)
+                / (2.0 - 2.0 * cos_zero_omega);
+            return Coefficients {
+                a1,
+                a2,
+                b0: gain,
+                b1: -2.0 * gain * cos_zero_omega,
+                b2: gain,
+            };
+        }
+    }
+
+    // weighted least squares for B1 and B2, normal equations of the 2x2 system
+    let lowest_omega = 0.05 * match_omega.min(0.5 * std::f64::consts::PI);
+    let omega_ratio = std::f64::consts::PI / lowest_omega;
+    let mut normal_matrix = [[0.0; 2]; 2];
+    let mut normal_vector = [0.0; 2];
+    for i in 0..MATCHED_FIT_POINTS {
+        let omega = lowest_omega * omega_ratio.powf(i as f64 / (MATCHED_FIT_POINTS - 1) as f64);
+        let fit_phi1 = phi1(omega);
+        let target = analog_squared_magnitude(omega) * denominator(fit_phi1);
+        if target <= 0.0 || target.is_nan() {
+            continue;
+        }
+        let weight = 1.0 / (target * target);
+        let row = [fit_phi1, 4.0 * fit_phi1 * (1.0 - fit_phi1)];
+        let rhs = target - numerator_b0 * (1.0 - fit_phi1);
+        for r in 0..2 {
+            normal_vector[r] += weight * row[r] * rhs;
+            for c in 0..2 {
+                normal_matrix[r][c] += weight * row[r] * row[c];
+            }
+        }
+    }
+    let determinant =
+        normal_matrix[0][0] * normal_matrix[1][1] - normal_matrix[0][1] * normal_matrix[1][0];
+    let numerator_b1 = ((normal_vector[0] * normal_matrix[1][1]
+        - normal_matrix[0][1] * normal_vector[1])
+        / determinant)
+        .max(0.0);
+    let numerator_b2 = (normal_matrix[0][0] * normal_vector[1]
+        - normal_matrix[1][0] * normal_vector[0])
+        / determinant;
+
+    let (sqrt_b0, sqrt_b1) = (numerator_b0.sqrt(), numerator_b1.sqrt());
+    let w = 0.5 * (sqrt_b0 + sqrt_b1);
+    let b0 = 0.5 * (w + (w * w + numerator_b2).max(0.0).sqrt());
+    let b2 = if b0 > 0.0 {
+        -numerator_b2 / (4.0 * b0)
+    } else {
+        0.0
+    };
+    Coefficients {
+        a1,
+        a2,
+        b0,
+        b1: 0.5 * (sqrt_b0 - sqrt_b1),
+        b2,
+    }
+}
+
+fn matched_z_f64(analog: &analog::AnalogBiquad<f64>, sample_rate: f64) -> Coefficients<f64> {
+    let omega0 = utils::omega(analog.frequency, sample_rate);
+    let (a1, a2) = matched_roots([analog.a0, analog.a1, analog.a2], omega0);
+    let (mut b1, mut b2) = matched_roots([analog.b0, analog.b1, analog.b2], omega0);
+    for _ in polynomial_degree([analog.b0, analog.b1, analog.b2])
+        ..polynomial_degree([analog.a0, analog.a1, analog.a2])
+    {
+        // multiply with (1 + z^-1)
+        b2 += b1;
+        b1 += 1.0;
+    }
+
+    let dc_gain = analog.b0 / analog.a0;
+    let match_omega = if dc_gain != 0.0 {
+        0.0
+    } else {
+        omega0.min(0.9 * std::f64::consts::PI)
+    };
+    let analog_magnitude = analog
+        .frequency_response(match_omega / std::f64::consts::TAU * sample_rate)
+        .norm();
+    let z = num::Complex::from_polar(1.0, -match_omega);
+    let digital_magnitude = ((z * (z * b2 + b1) + 1.0) / (z * (z * a2 + a1) + 1.0)).norm();
+    let gain = dc_gain.signum() * analog_magnitude / digital_magnitude;
+    Coefficients {
+        a1,
+        a2,
+        b0: gain,
+        b1: gain * b1,
+        b2: gain * b2,
+    }
+}
+
+fn impulse_invariant_f64(
+    analog: &analog::AnalogBiquad<f64>,
+    sample_rate: f64,
+) -> Coefficients<f64> {
+    // everything is normalized to the frequency of the prototype, so the sampling interval is
+    // omega0 and T * h(n * T) of the normalized prototype is the digital impulse response
+    let period = utils::omega(analog.frequency, sample_rate);
+    if analog.is_zeroth_order() {
+        return Coefficients::from_volume_linear(analog.b0 / analog.a0);
+    }
+    if analog.is_first_order() {
+        // (b0 + b1*s) / (a0 + a1*s) = direct + residue / (s + decay)
+        let direct = analog.b1 / analog.a1;
+        let residue = (analog.b0 - direct * analog.a0) / analog.a1;
+        let pole = (-analog.a0 / analog.a1 * period).exp();
+        let half_residue = 0.5 * period * residue;
+        return Coefficients {
+            a1: -pole,
+            a2: 0.0,
+            b0: half_residue + direct,
+            b1: (half_residue - direct) * pole,
+            b2: 0.0,
+        };
+    }
+
+    // (b0 + b1*s + b2*s^2) / (a0 + a1*s + a2*s^2) = direct + (m0 + m1*s) / ((s + sigma)^2 - d)
+    // with impulse response exp(-sigma*t) * (m1*C(t) + (m0 - m1*sigma)*S(t)),
+    // C = cos(sqrt(-d)*t) and S = sin(sqrt(-d)*t)/sqrt(-d) (cosh and sinh for d > 0)
+    let direct = analog.b2 / analog.a2;
+    let m0 = (analog.b0 - direct * analog.a0) / analog.a2;
+    let m1 = (analog.b1 - direct * analog.a1) / analog.a2;
+    let sigma = 0.5 * analog.a1 / analog.a2;
+    let discriminant = sigma * sigma - analog.a0 / analog.a2;
+    let (c, s) = if discriminant < 0.0 {
+        let omega = (-discriminant).sqrt();
+        ((omega * period).cos(), (omega * period).sin() / omega)
+    } else if discriminant > 0.0 {
+        let omega = discriminant.sqrt();
+        ((omega * period).cosh(), (omega * period).sinh() / omega)
+    } else {
+        (1.0, period)
+    };
+    let decay = (-sigma * period).exp();
+    let a1 = -2.0 * decay * c;
+    let a2 = decay * decay;
+    // constant part of the response, added as constant * (1 + a1*z^-1 + a2*z^-2)
+    let constant = direct - 0.5 * period * m1;
+    Coefficients {
+        a1,
+        a2,
+        b0: period * m1 + constant,
+        b1: period * decay * (-m1 * c + (m0 - m1 * sigma) * s) + constant * a1,
+        b2: constant * a2,
+    }
+}
+
+fn polynomial_degree(polynomial: [f64; 3]) -> usize {
+    polynomial
+        .iter()
+        .rposition(|coefficient| *coefficient != 0.0)
+        .unwrap_or(0)
+}
+
+/// Coefficients x1, x2 of 1 + x1*z^-1 + x2*z^-2, with the roots of the analog polynomial
+/// p0 + p1*s + p2*s^2 mapped with z = exp(s*T)
+fn matched_roots(polynomial: [f64; 3], omega0: f64) -> (f64, f64) {
+    let [p0, p1, p2] = polynomial;
+    if p2 == 0.0 {
+        if p1 == 0.0 {
+            return (0.0, 0.0);
+        }
+        return (-(-p0 / p1 * omega0).exp(), 0.0);
+    }
+    let half_p = 0.5 * p1 / p2;
+    let discriminant = half_p * half_p - p0 / p2;
+    let decay = (-half_p * omega0).exp();
+    let oscillation = if discriminant < 0.0 {
+        ((-discriminant).sqrt() * omega0).cos()
+    } else {
+        (discriminant.sqrt() * omega0).cosh()
+    };
+    (-2.0 * decay * oscillation, decay * decay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(frequency: f32, q: f32, gain_db: f32) -> eq::Eq<f32> {
+        eq::Eq {
+            gain: eq::Gain::Db(gain_db),
+            frequency: eq::Frequency::Hz(frequency),
+            bandwidth: eq::Bandwidth::Q(q),
+            eq_type: eq::EqType::Peak,
+            slope: eq::Slope::default(),
+            alignment: eq::Alignment::default(),
+            design: eq::Design::default(),
+            peak_shape: eq::PeakShape::default(),
+        }
+    }
+
+    #[test]
+    fn try_from_eq_reports_invalid_parameters() {
+        let sample_rate = 48000.0;
+        let try_from_eq = |eq: &eq::Eq<f32>| Coefficients::try_from_eq(eq, sample_rate).err();
+
+        assert_eq!(try_from_eq(&peak(1000.0, 0.7, 6.0)), None);
+        assert_eq!(
+            try_from_eq(&peak(30000.0, 0.7, 6.0)),
+            Some(Error::FrequencyAboveNyquist {
+                frequency: 30000.0,
+                nyquist_frequency: 24000.0
+            })
+        );
+        assert_eq!(
+            try_from_eq(&peak(0.0, 0.7, 6.0)),
+            Some(Error::NonPositiveFrequency { frequency: 0.0 })
+        );
+        assert_eq!(
+            try_from_eq(&peak(1000.0, -1.0, 6.0)),
+            Some(Error::NonPositiveQ { q: -1.0 })
+        );
+        assert_eq!(
+            try_from_eq(&peak(1000.0, 0.7, f32::INFINITY)),
+            Some(Error::NonFiniteGain {
+                gain_db: f32::INFINITY
+            })
+        );
+        // in f32 a very low and narrow resonance rounds onto the unit circle
+        assert_eq!(try_from_eq(&peak(0.001, 100.0, 6.0)), Some(Error::Unstable));
+
+        // parameters that aren't used by the eq type are not checked
+        let mut volume = peak(30000.0, -1.0, 6.0);
+        volume.eq_type = eq::EqType::Volume;
+        assert_eq!(try_from_eq(&volume), None);
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            Error::FrequencyAboveNyquist {
+                frequency: 30000.0_f32,
+                nyquist_frequency: 24000.0
+            }
+            .to_string(),
+            "frequency 30000 Hz is not below Nyquist (24000 Hz)"
+        );
+        assert_eq!(
+            Error::NonPositiveQ { q: -1.0_f64 }.to_string(),
+            "q -1 is not positive"
+        );
+    }
 }
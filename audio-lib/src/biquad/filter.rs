@@ -30,12 +30,36 @@ impl<F: utils::Float> State<F> {
         processed
     }
 
+    pub fn process_block(&mut self, coefficients: &Coefficients<F>, samples: &mut [F]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(coefficients, *sample);
+        }
+    }
+
+    pub fn process_block_into(
+        &mut self,
+        coefficients: &Coefficients<F>,
+        input: &[F],
+        output: &mut [F],
+    ) {
+        assert_eq!(input.len(), output.len());
+        for (output_sample, input_sample) in output.iter_mut().zip(input) {
+            *output_sample = self.process(coefficients, *input_sample);
+        }
+    }
+
     pub fn reset(&mut self) {
         self.input_state.fill(F::ZERO);
         self.output_state.fill(F::ZERO);
     }
 }
 
+impl<F: utils::Float> Default for State<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug)]
 pub struct Filter<F: utils::Float> {
     coefficients: Coefficients<F>,
@@ -61,6 +85,15 @@ impl<F: utils::Float> Filter<F> {
         self.state.process(&self.coefficients, sample)
     }
 
+    pub fn process_block(&mut self, samples: &mut [F]) {
+        self.state.process_block(&self.coefficients, samples);
+    }
+
+    pub fn process_block_into(&mut self, input: &[F], output: &mut [F]) {
+        self.state
+            .process_block_into(&self.coefficients, input, output);
+    }
+
     pub fn reset_state(&mut self) {
         self.state.reset();
     }
@@ -0,0 +1,91 @@
+use crate::biquad::{coefficients, topology};
+use crate::utils;
+
+/// Coefficients of a second order Gray-Markel lattice-ladder structure: the reflection
+/// coefficients k1, k2 realise the poles, the ladder taps v0, v1, v2 on the backward signals
+/// realise the zeros. The section is stable, if and only if |k1| < 1 and |k2| < 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coefficients<F: utils::Float> {
+    pub k1: F,
+    pub k2: F,
+    pub v0: F,
+    pub v1: F,
+    pub v2: F,
+}
+
+impl<F: utils::Float> Coefficients<F> {
+    pub fn from_biquad(biquad: &coefficients::Coefficients<F>) -> Self {
+        let k2 = biquad.a2;
+        let k1 = biquad.a1 / (F::ONE + biquad.a2);
+        // the backward signals have the reversed denominator polynomials of the lower orders
+        // as numerators: 1, k1 + z^-1 and a2 + a1 * z^-1 + z^-2
+        let v2 = biquad.b2;
+        let v1 = biquad.b1 - v2 * biquad.a1;
+        let v0 = biquad.b0 - v1 * k1 - v2 * biquad.a2;
+        Self { k1, k2, v0, v1, v2 }
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Coefficients<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        Coefficients {
+            k1: cast(self.k1),
+            k2: cast(self.k2),
+            v0: cast(self.v0),
+            v1: cast(self.v1),
+            v2: cast(self.v2),
+        }
+    }
+}
+
+/// The delayed backward signals of both stages
+#[derive(Debug)]
+pub struct State<F: utils::Float> {
+    backward: [F; 2],
+}
+
+impl<F: utils::Float> State<F> {
+    pub const fn new() -> Self {
+        Self {
+            backward: [F::ZERO; 2],
+        }
+    }
+
+    pub fn process(&mut self, coefficients: &Coefficients<F>, sample: F) -> F {
+        let forward1 = sample - coefficients.k2 * self.backward[1];
+        let forward0 = forward1 - coefficients.k1 * self.backward[0];
+        let backward2 = coefficients.k2 * forward1 + self.backward[1];
+        let backward1 = coefficients.k1 * forward0 + self.backward[0];
+        self.backward = [forward0, backward1];
+        coefficients.v0 * forward0 + coefficients.v1 * backward1 + coefficients.v2 * backward2
+    }
+
+    pub fn reset(&mut self) {
+        self.backward.fill(F::ZERO);
+    }
+}
+
+impl<F: utils::Float> Default for State<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: utils::Float> topology::Section for State<F> {
+    type Float = F;
+    type Coefficients = Coefficients<F>;
+
+    fn make_coefficients(biquad: &coefficients::Coefficients<f64>) -> Self::Coefficients {
+        Coefficients::from_biquad(biquad).cast()
+    }
+
+    fn process(&mut self, coefficients: &Self::Coefficients, sample: F) -> F {
+        self.process(coefficients, sample)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+pub type CascadeCoefficients<F> = topology::CascadeCoefficients<State<F>>;
+pub type CascadeState<F> = topology::CascadeState<State<F>>;
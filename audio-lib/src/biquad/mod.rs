@@ -1,3 +1,12 @@
+pub mod analog;
+pub mod approximation;
+pub mod cascade;
 pub mod coefficients;
 pub mod filter;
+pub mod lattice;
+pub mod pole_zero;
+pub mod simd;
+pub mod svf;
+pub mod tdf2;
+pub mod topology;
 pub mod utils;
@@ -0,0 +1,327 @@
+use crate::biquad::{cascade, coefficients};
+use crate::utils;
+use num::Complex;
+
+/// Filter of arbitrary order, given by its zeros, poles and gain:
+/// H(z) = gain * prod(1 - zero * z^-1) / prod(1 - pole * z^-1).
+/// Roots with an imaginary part stand for the conjugate pair, so only one of both is given,
+/// real roots stand for themselves.
+#[derive(Debug, Clone)]
+pub struct PoleZero<F: utils::Float> {
+    pub zeros: Vec<Complex<F>>,
+    pub poles: Vec<Complex<F>>,
+    pub gain: F,
+}
+
+/// A real root or a conjugate pair, stored with non negative imaginary part
+#[derive(Debug, Clone, Copy)]
+enum Root<F: utils::Float> {
+    Real(F),
+    Pair(Complex<F>),
+}
+
+impl<F: utils::Float> Root<F> {
+    fn new(root: Complex<F>) -> Self {
+        if root.im == F::ZERO {
+            Self::Real(root.re)
+        } else {
+            Self::Pair(Complex::new(root.re, root.im.abs()))
+        }
+    }
+
+    fn is_real(&self) -> bool {
+        matches!(self, Self::Real(_))
+    }
+
+    fn is_pair(&self) -> bool {
+        matches!(self, Self::Pair(_))
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            Self::Real(_) => 1,
+            Self::Pair(_) => 2,
+        }
+    }
+
+    fn complex(&self) -> Complex<F> {
+        match self {
+            Self::Real(root) => Complex::from(*root),
+            Self::Pair(root) => *root,
+        }
+    }
+
+    /// Distance to the unit circle, small values need the most care
+    fn criticality(&self) -> F {
+        (F::ONE - self.complex().norm()).abs()
+    }
+}
+
+impl<F: utils::Float> PoleZero<F> {
+    pub fn new(zeros: Vec<Complex<F>>, poles: Vec<Complex<F>>, gain: F) -> Self {
+        Self { zeros, poles, gain }
+    }
+
+    /// Number of individual poles or zeros, whatever is larger, conjugate pairs count twice
+    pub fn order(&self) -> usize {
+        let count = |roots: &[Complex<F>]| roots.iter().map(|root| Root::new(*root).count()).sum();
+        usize::max(count(&self.zeros), count(&self.poles))
+    }
+
+    /// Groups the roots into second order sections, similar to the "nearest" pairing of
+    /// scipy's zpk2sos: starting with the pole closest to the unit circle, each pole pair gets
+    /// the zeros closest to it, such that zeros can cancel the resonances of nearby poles.
+    /// Missing poles and zeros are placed at the origin, sections with a single real pole and
+    /// zero are first order sections. The sections are ordered with the poles closest to the
+    /// unit circle last, and the gain is applied in the first section.
+    pub fn to_cascade(&self) -> cascade::Coefficients<F> {
+        let num_sections = self.order().div_ceil(2);
+        if num_sections == 0 {
+            return cascade::Coefficients::from_section(
+                coefficients::Coefficients::from_volume_linear(self.gain),
+            );
+        }
+        let mut zeros = padded_roots(&self.zeros, 2 * num_sections);
+        let mut poles = padded_roots(&self.poles, 2 * num_sections);
+
+        let mut sections = Vec::with_capacity(num_sections);
+        while let Some(pole) = take_most_critical(&mut poles, |_| true) {
+            let num_real_zeros = zeros.iter().filter(|zero| zero.is_real()).count();
+            let ((a1, a2), (b1, b2)) = match pole {
+                Root::Pair(pole) => {
+                    let zeros = if zeros.iter().any(Root::is_pair) {
+                        take_nearest_pair(&mut zeros, pole)
+                    } else {
+                        take_nearest_reals(&mut zeros, pole, pole)
+                    };
+                    (pair_polynomial(pole), zeros)
+                }
+                Root::Real(pole) => {
+                    let other_pole = match take_most_critical(&mut poles, Root::is_real) {
+                        Some(Root::Real(other_pole)) => other_pole,
+                        _ => F::ZERO,
+                    };
+                    let zeros = if num_real_zeros >= 2 {
+                        take_nearest_reals(
+                            &mut zeros,
+                            Complex::from(pole),
+                            Complex::from(other_pole),
+                        )
+                    } else {
+                        take_nearest_pair(&mut zeros, Complex::from(pole))
+                    };
+                    (real_polynomial(pole, other_pole), zeros)
+                }
+            };
+            sections.push(coefficients::Coefficients {
+                a1,
+                a2,
+                b0: F::ONE,
+                b1,
+                b2,
+            });
+        }
+
+        sections.reverse();
+        let first_section = &mut sections[0];
+        first_section.b0 *= self.gain;
+        first_section.b1 *= self.gain;
+        first_section.b2 *= self.gain;
+        cascade::Coefficients::from_sections(sections)
+    }
+
+    /// Evaluates the product form directly, without going through the sections
+    pub fn frequency_response(&self, frequency: F, sample_rate: F) -> Complex<F> {
+        let z1 = Complex::from_polar(F::ONE, -utils::omega(frequency, sample_rate));
+        let factors = |roots: &[Complex<F>]| {
+            roots
+                .iter()
+                .map(|root| match Root::new(*root) {
+                    Root::Real(root) => -z1 * root + F::ONE,
+                    Root::Pair(root) => {
+                        let (c1, c2) = pair_polynomial(root);
+                        z1 * (z1 * c2 + c1) + F::ONE
+                    }
+                })
+                .fold(Complex::from(F::ONE), |product, factor| product * factor)
+        };
+        factors(&self.zeros) / factors(&self.poles) * self.gain
+    }
+}
+
+impl<F: utils::Float> From<PoleZero<F>> for cascade::Coefficients<F> {
+    fn from(pole_zero: PoleZero<F>) -> Self {
+        pole_zero.to_cascade()
+    }
+}
+
+/// Roots filled up with real roots at the origin, to the given count of individual roots
+fn padded_roots<F: utils::Float>(roots: &[Complex<F>], count: usize) -> Vec<Root<F>> {
+    let mut roots = roots
+        .iter()
+        .map(|root| Root::new(*root))
+        .collect::<Vec<_>>();
+    let missing = count - roots.iter().map(Root::count).sum::<usize>();
+    roots.extend(std::iter::repeat_n(Root::Real(F::ZERO), missing));
+    roots
+}
+
+fn take_most_critical<F: utils::Float>(
+    roots: &mut Vec<Root<F>>,
+    filter: impl Fn(&Root<F>) -> bool,
+) -> Option<Root<F>> {
+    let index = roots
+        .iter()
+        .enumerate()
+        .filter(|(_, root)| filter(root))
+        .min_by(|(_, a), (_, b)| a.criticality().partial_cmp(&b.criticality()).unwrap())
+        .map(|(index, _)| index)?;
+    Some(roots.swap_remove(index))
+}
+
+fn take_nearest<F: utils::Float>(
+    roots: &mut Vec<Root<F>>,
+    near: Complex<F>,
+    filter: impl Fn(&Root<F>) -> bool,
+) -> Option<Root<F>> {
+    let distance = |root: &Root<F>| (root.complex() - near).norm();
+    let index = roots
+        .iter()
+        .enumerate()
+        .filter(|(_, root)| filter(root))
+        .min_by(|(_, a), (_, b)| distance(a).partial_cmp(&distance(b)).unwrap())
+        .map(|(index, _)| index)?;
+    Some(roots.swap_remove(index))
+}
+
+fn take_nearest_pair<F: utils::Float>(roots: &mut Vec<Root<F>>, near: Complex<F>) -> (F, F) {
+    match take_nearest(roots, near, Root::is_pair) {
+        Some(Root::Pair(root)) => pair_polynomial(root),
+        _ => (F::ZERO, F::ZERO),
+    }
+}
+
+/// Takes the real root nearest to near and then the one nearest to other_near
+fn take_nearest_reals<F: utils::Float>(
+    roots: &mut Vec<Root<F>>,
+    near: Complex<F>,
+    other_near: Complex<F>,
+) -> (F, F) {
+    let root = take_nearest(roots, near, Root::is_real);
+    let other_root = take_nearest(roots, other_near, Root::is_real);
+    match (root, other_root) {
+        (Some(Root::Real(root)), Some(Root::Real(other_root))) => real_polynomial(root, other_root),
+        _ => (F::ZERO, F::ZERO),
+    }
+}
+
+/// c1, c2 of (1 - root * z^-1) * (1 - conj(root) * z^-1) = 1 + c1 * z^-1 + c2 * z^-2
+fn pair_polynomial<F: utils::Float>(root: Complex<F>) -> (F, F) {
+    (-F::TWO * root.re, root.norm_sqr())
+}
+
+/// c1, c2 of (1 - root * z^-1) * (1 - other_root * z^-1) = 1 + c1 * z^-1 + c2 * z^-2
+fn real_polynomial<F: utils::Float>(root: F, other_root: F) -> (F, F) {
+    (-(root + other_root), root * other_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::utils as biquad_utils;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn example() -> PoleZero<f64> {
+        PoleZero::new(
+            vec![
+                Complex::from_polar(1.0, 0.32),
+                Complex::from_polar(1.0, 2.1),
+                Complex::new(-1.0, 0.0),
+                Complex::new(0.3, 0.0),
+            ],
+            vec![
+                Complex::from_polar(0.5, 2.0),
+                Complex::new(0.2, 0.0),
+                Complex::from_polar(0.95, 0.3),
+                Complex::new(-0.4, 0.0),
+                Complex::new(0.8, 0.0),
+            ],
+            0.25,
+        )
+    }
+
+    #[test]
+    fn cascade_has_the_same_response() {
+        let sample_rate = 48000.0;
+        let pole_zero = example();
+        assert_eq!(pole_zero.order(), 7);
+        let coefficients = pole_zero.to_cascade();
+        assert_eq!(coefficients.sections().len(), 4);
+        assert!(biquad_utils::cascade::is_stable(&coefficients));
+
+        let frequency_response =
+            biquad_utils::cascade::make_frequency_response(coefficients.clone(), sample_rate);
+        for i in 0..100 {
+            let frequency = i as f64 * 240.0;
+            let expected = pole_zero.frequency_response(frequency, sample_rate);
+            let actual = frequency_response(frequency);
+            assert_approx_eq!(actual.re, expected.re, 1e-9);
+            assert_approx_eq!(actual.im, expected.im, 1e-9);
+        }
+
+        // processing with more than MAX_NUM_SECTIONS sections works as well
+        let longer = PoleZero::new(
+            vec![Complex::new(-1.0, 0.0); 10],
+            vec![Complex::new(0.5, 0.0); 10],
+            1.0,
+        );
+        let impulse_response = biquad_utils::cascade::impulse_response_for_coefficients(
+            longer.to_cascade(),
+            1e-12,
+            10,
+            10000,
+        );
+        // dc gain: 2^10 / 0.5^10
+        assert_approx_eq!(impulse_response.iter().sum::<f64>(), 1048576.0, 1e-6);
+    }
+
+    #[test]
+    fn pairs_nearest_roots() {
+        let coefficients = example().to_cascade();
+        let sections = coefficients.sections();
+
+        // the resonance closest to the unit circle is last, with the zeros next to it
+        let last = sections.last().unwrap();
+        let (a1, a2) = pair_polynomial(Complex::from_polar(0.95, 0.3));
+        let (b1, b2) = pair_polynomial(Complex::from_polar(1.0, 0.32));
+        assert_approx_eq!(last.a1, a1);
+        assert_approx_eq!(last.a2, a2);
+        assert_approx_eq!(last.b1, b1);
+        assert_approx_eq!(last.b2, b2);
+
+        // the most critical real poles are paired with each other, each with its nearest zero
+        let real_section = &sections[2];
+        assert_approx_eq!(real_section.a1, -(0.8 - 0.4));
+        assert_approx_eq!(real_section.a2, 0.8 * -0.4);
+        assert_approx_eq!(real_section.b1, -0.3);
+        assert_approx_eq!(real_section.b2, 0.0);
+
+        // the remaining real pole gets a pole at the origin, which cancels with a zero there,
+        // so a first order section results
+        let first = &sections[0];
+        assert_approx_eq!(first.a1, -0.2);
+        assert_eq!(first.a2, 0.0);
+        assert_eq!(first.b2, 0.0);
+        assert!(biquad_utils::is_first_order(first));
+        assert_approx_eq!(first.b0, 0.25);
+        assert_approx_eq!(first.b1, 0.25);
+    }
+
+    #[test]
+    fn without_roots_only_gain_is_left() {
+        let coefficients = PoleZero::<f32>::new(vec![], vec![], 0.5).to_cascade();
+        assert_eq!(coefficients.sections().len(), 1);
+        assert_eq!(coefficients.sections()[0].b0, 0.5);
+        assert_eq!(coefficients.sections()[0].a1, 0.0);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::biquad::{coefficients, topology};
+use crate::utils;
+
+/// Coefficients of a topology-preserving (trapezoidal integrated) state variable filter,
+/// following Andrew Simper's (Cytomic) "Linear Trap Optimised 2" structure. In the normalised
+/// analog domain, with the bilinear transform s = (1 - z^-1) / (1 + z^-1) / g, it realises
+/// H(s) = (m0 * s^2 + (m0 * k + m1) * s + m0 + m2) / (s^2 + k * s + 1).
+/// Since the states are the integrator outputs, changing coefficients between samples keeps
+/// the energy in the filter, unlike with the direct forms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coefficients<F: utils::Float> {
+    g: F,
+    k: F,
+    m0: F,
+    m1: F,
+    m2: F,
+    a1: F,
+    a2: F,
+    a3: F,
+}
+
+impl<F: utils::Float> Coefficients<F> {
+    /// g = tan(omega / 2) of the cutoff, k = 1 / q, m0, m1, m2 mix the high pass, band pass
+    /// and low pass outputs
+    pub fn new(g: F, k: F, m0: F, m1: F, m2: F) -> Self {
+        let a1 = F::ONE / (F::ONE + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+        Self {
+            g,
+            k,
+            m0,
+            m1,
+            m2,
+            a1,
+            a2,
+            a3,
+        }
+    }
+
+    pub fn from_lowpass(cutoff_frequency: F, q: F, sample_rate: F) -> Self {
+        Self::new(
+            g(cutoff_frequency, sample_rate),
+            F::ONE / q,
+            F::ZERO,
+            F::ZERO,
+            F::ONE,
+        )
+    }
+
+    pub fn from_highpass(cutoff_frequency: F, q: F, sample_rate: F) -> Self {
+        let k = F::ONE / q;
+        Self::new(g(cutoff_frequency, sample_rate), k, F::ONE, -k, -F::ONE)
+    }
+
+    pub fn from_bandpass(frequency: F, q: F, sample_rate: F) -> Self {
+        let k = F::ONE / q;
+        Self::new(g(frequency, sample_rate), k, F::ZERO, k, F::ZERO)
+    }
+
+    /// The state variable filter with the same transfer function as the biquad. This exists
+    /// for every stable biquad, since its poles can't be at z = 1 or z = -1.
+    pub fn from_biquad(biquad: &coefficients::Coefficients<F>) -> Self {
+        // polynomials in s after the inverse bilinear transform z^-1 = (1 - s) / (1 + s)
+        let a_2 = F::ONE - biquad.a1 + biquad.a2;
+        let a_1 = F::TWO * (F::ONE - biquad.a2);
+        let a_0 = F::ONE + biquad.a1 + biquad.a2;
+        let b_2 = biquad.b0 - biquad.b1 + biquad.b2;
+        let b_1 = F::TWO * (biquad.b0 - biquad.b2);
+        let b_0 = biquad.b0 + biquad.b1 + biquad.b2;
+
+        let g = (a_0 / a_2).sqrt();
+        let k = a_1 / (a_0 * a_2).sqrt();
+        let m0 = b_2 / a_2;
+        Self::new(g, k, m0, b_1 * g / a_0 - m0 * k, b_0 / a_0 - m0)
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Coefficients<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        Coefficients::new(
+            cast(self.g),
+            cast(self.k),
+            cast(self.m0),
+            cast(self.m1),
+            cast(self.m2),
+        )
+    }
+
+    pub fn g(&self) -> F {
+        self.g
+    }
+
+    pub fn k(&self) -> F {
+        self.k
+    }
+}
+
+impl<F: utils::Float> From<&coefficients::Coefficients<F>> for Coefficients<F> {
+    fn from(biquad: &coefficients::Coefficients<F>) -> Self {
+        Self::from_biquad(biquad)
+    }
+}
+
+fn g<F: utils::Float>(frequency: F, sample_rate: F) -> F {
+    (F::ONE_HALF * utils::omega(frequency, sample_rate)).tan()
+}
+
+#[derive(Debug)]
+pub struct State<F: utils::Float> {
+    ic1eq: F,
+    ic2eq: F,
+}
+
+impl<F: utils::Float> State<F> {
+    pub const fn new() -> Self {
+        Self {
+            ic1eq: F::ZERO,
+            ic2eq: F::ZERO,
+        }
+    }
+
+    pub fn process(&mut self, coefficients: &Coefficients<F>, sample: F) -> F {
+        let v3 = sample - self.ic2eq;
+        let v1 = coefficients.a1 * self.ic1eq + coefficients.a2 * v3;
+        let v2 = self.ic2eq + coefficients.a2 * self.ic1eq + coefficients.a3 * v3;
+        self.ic1eq = F::TWO * v1 - self.ic1eq;
+        self.ic2eq = F::TWO * v2 - self.ic2eq;
+        coefficients.m0 * sample + coefficients.m1 * v1 + coefficients.m2 * v2
+    }
+
+    pub fn reset(&mut self) {
+        self.ic1eq = F::ZERO;
+        self.ic2eq = F::ZERO;
+    }
+}
+
+impl<F: utils::Float> Default for State<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: utils::Float> topology::Section for State<F> {
+    type Float = F;
+    type Coefficients = Coefficients<F>;
+
+    fn make_coefficients(biquad: &coefficients::Coefficients<f64>) -> Self::Coefficients {
+        Coefficients::from_biquad(biquad).cast()
+    }
+
+    fn process(&mut self, coefficients: &Self::Coefficients, sample: F) -> F {
+        self.process(coefficients, sample)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+pub type CascadeCoefficients<F> = topology::CascadeCoefficients<State<F>>;
+pub type CascadeState<F> = topology::CascadeState<State<F>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::cascade;
+    use crate::eq;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn test_eqs() -> Vec<eq::Eq<f64>> {
+        let mut eqs = Vec::new();
+        for eq_type in eq::EqType::ALL {
+            for design in [eq::Design::Cookbook, eq::Design::MatchedZ] {
+                eqs.push(eq::Eq {
+                    gain: eq::Gain::Db(-7.5),
+                    frequency: eq::Frequency::Hz(1300.0),
+                    bandwidth: eq::Bandwidth::Q(2.5),
+                    eq_type: *eq_type,
+                    slope: eq::Slope::Db36,
+                    alignment: eq::Alignment::Butterworth,
+                    design,
+                    peak_shape: eq::PeakShape::default(),
+                });
+            }
+        }
+        eqs
+    }
+
+    #[test]
+    fn same_impulse_response_as_direct_form() {
+        let sample_rate = 48000.0;
+        for eq in test_eqs() {
+            let biquad = cascade::Coefficients::from_eq(&eq, sample_rate);
+            let svf = CascadeCoefficients::from(&biquad);
+            let mut biquad_state = cascade::State::new();
+            let mut svf_state = CascadeState::new();
+            for n in 0..2000 {
+                let sample = if n == 0 { 1.0 } else { 0.0 };
+                assert_approx_eq!(
+                    svf_state.process(&svf, sample),
+                    biquad_state.process(&biquad, sample),
+                    1e-9
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parameters_of_cookbook_designs() {
+        let sample_rate = 44100.0;
+        let (frequency, q): (f64, f64) = (2000.0, 3.0);
+        let expected_g = g(frequency, sample_rate);
+        for biquad in [
+            coefficients::Coefficients::from_lowpass(frequency, q, sample_rate),
+            coefficients::Coefficients::from_bandpass(frequency, q, sample_rate),
+            coefficients::Coefficients::from_notch(frequency, q, sample_rate),
+        ] {
+            let svf = Coefficients::from_biquad(&biquad);
+            assert_approx_eq!(svf.g(), expected_g);
+            assert_approx_eq!(svf.k(), 1.0 / q);
+        }
+
+        let lowpass = Coefficients::from_lowpass(frequency, q, sample_rate);
+        let from_biquad = Coefficients::from_biquad(&coefficients::Coefficients::from_lowpass(
+            frequency,
+            q,
+            sample_rate,
+        ));
+        assert_approx_eq!(from_biquad.m0, lowpass.m0);
+        assert_approx_eq!(from_biquad.m1, lowpass.m1);
+        assert_approx_eq!(from_biquad.m2, lowpass.m2);
+    }
+
+    #[test]
+    fn stays_bounded_under_fast_modulation() {
+        // a resonant low pass swept by an octave every few samples, in f32
+        let sample_rate = 48000_f32;
+        let mut state = State::new();
+        let mut max_output = 0_f32;
+        for n in 0..48000 {
+            let sweep = ((n / 4) % 2) as f32;
+            let frequency = 200_f32 * 2_f32.powf(6_f32 * sweep);
+            let coefficients = Coefficients::from_lowpass(frequency, 10_f32, sample_rate);
+            let output = state.process(&coefficients, (n as f32 * 0.05).sin());
+            max_output = max_output.max(output.abs());
+        }
+        assert!(max_output.is_finite());
+        assert!(max_output < 100_f32);
+
+        // the steady state doesn't depend on the past modulation
+        let coefficients = Coefficients::from_lowpass(1000_f32, 0.7, sample_rate);
+        let mut settled = 0_f32;
+        for _ in 0..4800 {
+            settled = state.process(&coefficients, 1_f32);
+        }
+        assert_approx_eq!(settled, 1_f32, 1e-4);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::biquad::{coefficients::Coefficients, topology};
+use crate::utils;
+
+/// Transposed direct form II, with the same coefficients as direct form I (see filter), but
+/// only two states
+#[derive(Debug)]
+pub struct State<F: utils::Float> {
+    state: [F; 2],
+}
+
+impl<F: utils::Float> State<F> {
+    pub const fn new() -> Self {
+        Self {
+            state: [F::ZERO; 2],
+        }
+    }
+
+    pub fn process(&mut self, coefficients: &Coefficients<F>, sample: F) -> F {
+        let processed = coefficients.b0 * sample + self.state[0];
+        self.state[0] = coefficients.b1 * sample - coefficients.a1 * processed + self.state[1];
+        self.state[1] = coefficients.b2 * sample - coefficients.a2 * processed;
+        processed
+    }
+
+    pub fn reset(&mut self) {
+        self.state.fill(F::ZERO);
+    }
+}
+
+impl<F: utils::Float> Default for State<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: utils::Float> topology::Section for State<F> {
+    type Float = F;
+    type Coefficients = Coefficients<F>;
+
+    fn make_coefficients(biquad: &Coefficients<f64>) -> Self::Coefficients {
+        biquad.cast()
+    }
+
+    fn process(&mut self, coefficients: &Self::Coefficients, sample: F) -> F {
+        self.process(coefficients, sample)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+pub type CascadeCoefficients<F> = topology::CascadeCoefficients<State<F>>;
+pub type CascadeState<F> = topology::CascadeState<State<F>>;
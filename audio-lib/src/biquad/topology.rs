@@ -0,0 +1,372 @@
+use crate::biquad::{cascade, coefficients, filter};
+use crate::{eq, utils};
+use enum_table::Enumable;
+
+/// Structure that realises the biquad sections, all of them have the same transfer function.
+/// DirectForm1 (see filter) is the cheapest, but its states are past inputs and outputs, so it
+/// zippers or blows up when the coefficients change quickly. TransposedDirectForm2 (see tdf2)
+/// needs only two states, but shares the coefficient sensitivity of the direct forms.
+/// StateVariable (see svf) stays well behaved under modulation and is the least sensitive to
+/// quantization for low frequencies, Lattice (see lattice) is in between.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Topology {
+    #[default]
+    DirectForm1,
+    StateVariable,
+    TransposedDirectForm2,
+    Lattice,
+}
+
+impl Topology {
+    pub const ALL: &'static [Topology] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = [
+        "Direct Form I",
+        "State Variable",
+        "Transposed Direct Form II",
+        "Lattice",
+    ];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+}
+
+impl TryFrom<usize> for Topology {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("Topology for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for Topology {
+    type Error = &'static str;
+
+    fn try_from(topology_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES
+            .iter()
+            .position(|&name| name == topology_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("Topology {} is not defined", value)),
+        }
+    }
+}
+
+/// A structure realising one biquad section, which is its state, with the coefficients it needs
+pub trait Section: Default {
+    type Float: utils::Float;
+    type Coefficients: Clone;
+
+    /// The conversion runs in f64, since the structures that are less sensitive to coefficient
+    /// quantization than the direct forms only profit from a biquad designed in f64 as well
+    fn make_coefficients(biquad: &coefficients::Coefficients<f64>) -> Self::Coefficients;
+    fn process(&mut self, coefficients: &Self::Coefficients, sample: Self::Float) -> Self::Float;
+    fn reset(&mut self);
+
+    /// Structures with a faster way than sample by sample can override this
+    fn process_block(&mut self, coefficients: &Self::Coefficients, samples: &mut [Self::Float]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(coefficients, *sample);
+        }
+    }
+}
+
+impl<F: utils::Float> Section for filter::State<F> {
+    type Float = F;
+    type Coefficients = coefficients::Coefficients<F>;
+
+    fn make_coefficients(biquad: &coefficients::Coefficients<f64>) -> Self::Coefficients {
+        biquad.cast()
+    }
+
+    fn process(&mut self, coefficients: &Self::Coefficients, sample: F) -> F {
+        self.process(coefficients, sample)
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+
+    fn process_block(&mut self, coefficients: &Self::Coefficients, samples: &mut [F]) {
+        self.process_block(coefficients, samples);
+    }
+}
+
+/// Counterpart of cascade::Coefficients for any section structure
+pub struct CascadeCoefficients<S: Section> {
+    sections: cascade::SectionsOf<S::Coefficients>,
+}
+
+impl<S: Section> CascadeCoefficients<S> {
+    pub fn from_eq(eq: &eq::Eq<f64>, sample_rate: f64) -> Self {
+        Self::from(&cascade::Coefficients::from_eq(eq, sample_rate))
+    }
+
+    pub fn muted() -> Self {
+        Self::from(&cascade::Coefficients::muted())
+    }
+
+    pub fn sections(&self) -> &[S::Coefficients] {
+        &self.sections
+    }
+}
+
+impl<S: Section> Clone for CascadeCoefficients<S> {
+    fn clone(&self) -> Self {
+        Self {
+            sections: self.sections.clone(),
+        }
+    }
+}
+
+impl<S: Section> From<&cascade::Coefficients<f64>> for CascadeCoefficients<S> {
+    fn from(coefficients: &cascade::Coefficients<f64>) -> Self {
+        Self {
+            sections: coefficients
+                .sections()
+                .iter()
+                .map(S::make_coefficients)
+                .collect(),
+        }
+    }
+}
+
+/// Counterpart of cascade::State for any section structure
+pub struct CascadeState<S: Section> {
+    section_states: cascade::SectionsOf<S>,
+}
+
+impl<S: Section> CascadeState<S> {
+    pub fn new() -> Self {
+        Self {
+            section_states: (0..cascade::MAX_NUM_SECTIONS)
+                .map(|_| S::default())
+                .collect(),
+        }
+    }
+
+    pub fn process(&mut self, coefficients: &CascadeCoefficients<S>, sample: S::Float) -> S::Float {
+        if self.section_states.len() < coefficients.sections.len() {
+            self.section_states
+                .resize_with(coefficients.sections.len(), S::default);
+        }
+        let mut output = sample;
+        for (state, section) in self
+            .section_states
+            .iter_mut()
+            .zip(coefficients.sections.iter())
+        {
+            output = state.process(section, output);
+        }
+        output
+    }
+
+    /// See cascade::State::process_block
+    pub fn process_block(
+        &mut self,
+        coefficients: &CascadeCoefficients<S>,
+        samples: &mut [S::Float],
+    ) {
+        if self.section_states.len() < coefficients.sections.len() {
+            self.section_states
+                .resize_with(coefficients.sections.len(), S::default);
+        }
+        for (state, section) in self
+            .section_states
+            .iter_mut()
+            .zip(coefficients.sections.iter())
+        {
+            state.process_block(section, samples);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for state in self.section_states.iter_mut() {
+            state.reset();
+        }
+    }
+}
+
+impl<S: Section> Default for CascadeState<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::{lattice, svf, tdf2};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn process<S: Section>(
+        coefficients: &cascade::Coefficients<f64>,
+        input: &[S::Float],
+    ) -> Vec<S::Float> {
+        let coefficients = CascadeCoefficients::<S>::from(coefficients);
+        let mut state = CascadeState::<S>::new();
+        input
+            .iter()
+            .map(|sample| state.process(&coefficients, *sample))
+            .collect()
+    }
+
+    /// Deterministic white noise in [-0.5, 0.5)
+    fn noise(length: usize) -> Vec<f64> {
+        let mut seed = 12345_u32;
+        (0..length)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                seed as f64 / (u32::MAX as f64 + 1.0) - 0.5
+            })
+            .collect()
+    }
+
+    /// Energy of the error relative to the energy of the expected signal
+    fn error_db(actual: &[f32], expected: &[f64]) -> f64 {
+        let error: f64 = actual
+            .iter()
+            .zip(expected)
+            .map(|(actual, expected)| (*actual as f64 - expected).powi(2))
+            .sum();
+        let signal: f64 = expected.iter().map(|expected| expected.powi(2)).sum();
+        10.0 * (error / signal).log10()
+    }
+
+    struct Errors {
+        direct_form1: f64,
+        transposed_direct_form2: f64,
+        state_variable: f64,
+        lattice: f64,
+    }
+
+    /// Errors of lowpass filters processing noise in f32, compared to direct form I in f64
+    fn lowpass_errors(frequency: f64, q: f64) -> Errors {
+        let coefficients = cascade::Coefficients::from_section(
+            coefficients::Coefficients::from_lowpass(frequency, q, 48000.0),
+        );
+        let input = noise(96000);
+        let input_f32: Vec<f32> = input.iter().map(|sample| *sample as f32).collect();
+        let expected = process::<filter::State<f64>>(&coefficients, &input);
+        Errors {
+            direct_form1: error_db(
+                &process::<filter::State<f32>>(&coefficients, &input_f32),
+                &expected,
+            ),
+            transposed_direct_form2: error_db(
+                &process::<tdf2::State<f32>>(&coefficients, &input_f32),
+                &expected,
+            ),
+            state_variable: error_db(
+                &process::<svf::State<f32>>(&coefficients, &input_f32),
+                &expected,
+            ),
+            lattice: error_db(
+                &process::<lattice::State<f32>>(&coefficients, &input_f32),
+                &expected,
+            ),
+        }
+    }
+
+    #[test]
+    fn all_topologies_have_the_same_impulse_response() {
+        let sample_rate = 48000.0;
+        let eqs = [
+            (eq::EqType::Peak, eq::Slope::Db12),
+            (eq::EqType::LowShelf, eq::Slope::Db12),
+            (eq::EqType::HighPass, eq::Slope::Db36),
+            (eq::EqType::FirstOrderLowPass, eq::Slope::Db12),
+            (eq::EqType::AllPass, eq::Slope::Db12),
+        ]
+        .map(|(eq_type, slope)| eq::Eq {
+            gain: eq::Gain::Db(4.5),
+            frequency: eq::Frequency::Hz(700.0),
+            bandwidth: eq::Bandwidth::Q(1.7),
+            eq_type,
+            slope,
+            alignment: eq::Alignment::Butterworth,
+            design: eq::Design::Cookbook,
+            peak_shape: eq::PeakShape::default(),
+        });
+        let mut impulse = vec![0.0; 1000];
+        impulse[0] = 1.0;
+        for eq in eqs.iter() {
+            let coefficients = cascade::Coefficients::from_eq(eq, sample_rate);
+            let expected = process::<filter::State<f64>>(&coefficients, &impulse);
+            for actual in [
+                process::<tdf2::State<f64>>(&coefficients, &impulse),
+                process::<svf::State<f64>>(&coefficients, &impulse),
+                process::<lattice::State<f64>>(&coefficients, &impulse),
+            ] {
+                for (actual, expected) in actual.iter().zip(expected.iter()) {
+                    assert_approx_eq!(actual, expected, 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn state_variable_is_robust_for_low_frequencies_and_high_q() {
+        for (frequency, q) in [(10.0, 10.0), (20.0, 5.0), (40.0, 20.0)] {
+            let errors = lowpass_errors(frequency, q);
+            // the direct forms suffer from the quantization of a1 close to -2 and a2 close
+            // to 1 in f32, which moves the poles considerably
+            assert!(errors.state_variable < -60.0);
+            assert!(errors.direct_form1 > errors.state_variable + 15.0);
+            assert!(errors.transposed_direct_form2 > errors.state_variable + 15.0);
+            // the lattice's k2 = a2 is quantized just as badly
+            assert!(errors.lattice > errors.state_variable + 5.0);
+        }
+    }
+
+    #[test]
+    fn all_topologies_are_fine_for_mid_frequencies() {
+        let errors = lowpass_errors(1000.0, 5.0);
+        for error in [
+            errors.direct_form1,
+            errors.transposed_direct_form2,
+            errors.state_variable,
+            errors.lattice,
+        ] {
+            assert!(error < -95.0);
+        }
+    }
+
+    #[test]
+    fn transposed_direct_form2_has_the_higher_noise_floor() {
+        // with coefficients that are exact in f32, only the rounding of the processing is left
+        let coefficients = cascade::Coefficients::from_section(
+            coefficients::Coefficients::from_lowpass(20.0, 5.0, 48000.0)
+                .cast::<f32>()
+                .cast::<f64>(),
+        );
+        let input = noise(96000);
+        let input_f32: Vec<f32> = input.iter().map(|sample| *sample as f32).collect();
+        let expected = process::<filter::State<f64>>(&coefficients, &input);
+        let direct_form1 = error_db(
+            &process::<filter::State<f32>>(&coefficients, &input_f32),
+            &expected,
+        );
+        let transposed_direct_form2 = error_db(
+            &process::<tdf2::State<f32>>(&coefficients, &input_f32),
+            &expected,
+        );
+        assert!(direct_form1 < -55.0);
+        assert!(transposed_direct_form2 > direct_form1);
+    }
+}
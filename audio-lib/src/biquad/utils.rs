@@ -1,3 +1,4 @@
+use crate::biquad::cascade::Coefficients as CascadeCoefficients;
 use crate::biquad::{coefficients::Coefficients, filter::*};
 use crate::utils;
 use num::Complex;
@@ -10,6 +11,12 @@ pub fn process_sequential<F: utils::Float>(filters: &mut [Filter<F>], sample: F)
     output
 }
 
+pub fn process_sequential_block<F: utils::Float>(filters: &mut [Filter<F>], samples: &mut [F]) {
+    for filter in filters.iter_mut() {
+        filter.process_block(samples);
+    }
+}
+
 pub fn make_transfer_function<F: utils::Float>(
     coefficients: Coefficients<F>,
 ) -> impl Fn(num::Complex<F>) -> Complex<F> {
@@ -38,6 +45,87 @@ pub fn make_frequency_response<F: utils::Float>(
     }
 }
 
+/// Group delay in seconds, evaluated analytically from the transfer function: for a polynomial
+/// P(x) = p0 + p1 x + p2 x^2 in x = z^-1 = e^(-j omega), -d arg(P) / d omega equals
+/// Re(x P'(x) / P(x)), and the delay of the section is that of the numerator minus that of the
+/// denominator. At zeros on the unit circle, the delay is not finite.
+pub fn make_group_delay_response<F: utils::Float>(
+    coefficients: Coefficients<F>,
+    sample_rate: F,
+) -> impl Fn(F) -> F {
+    move |frequency| section_group_delay(&coefficients, frequency, sample_rate)
+}
+
+/// Phase without jumps of 2 pi, see multiband::make_unwrapped_phase_response
+pub fn make_unwrapped_phase_response<F: utils::Float>(
+    coefficients: Coefficients<F>,
+    sample_rate: F,
+) -> impl Fn(F) -> F {
+    multiband::make_unwrapped_phase_response([coefficients], sample_rate)
+}
+
+/// Phase delay -phase / omega in seconds, based on the unwrapped phase
+pub fn make_phase_delay_response<F: utils::Float>(
+    coefficients: Coefficients<F>,
+    sample_rate: F,
+) -> impl Fn(F) -> F {
+    utils::make_phase_delay_response(make_unwrapped_phase_response(coefficients, sample_rate))
+}
+
+fn section_group_delay<F: utils::Float>(
+    coefficients: &Coefficients<F>,
+    frequency: F,
+    sample_rate: F,
+) -> F {
+    let x = Complex::from_polar(F::ONE, -utils::omega(frequency, sample_rate));
+    let delay_in_samples =
+        polynomial_group_delay([coefficients.b0, coefficients.b1, coefficients.b2], x)
+            - polynomial_group_delay([F::ONE, coefficients.a1, coefficients.a2], x);
+    delay_in_samples / sample_rate
+}
+
+fn polynomial_group_delay<F: utils::Float>(polynomial: [F; 3], x: Complex<F>) -> F {
+    let [p0, p1, p2] = polynomial.map(Complex::from);
+    let x_squared = x * x;
+    ((p1 * x + Complex::from(F::TWO) * p2 * x_squared) / (p0 + p1 * x + p2 * x_squared)).re
+}
+
+/// Phase of the section at omega, continuous in omega, but with an arbitrary offset of a multiple
+/// of 2 pi. With the roots r of the polynomials in x = e^(-j omega), every factor x - r is either
+/// written as -r (1 - x / r) for |r| >= 1, or as x (1 - r / x) for |r| < 1. The phase of the
+/// parentheses stays within (-pi / 2, pi / 2), so it doesn't wrap, and the phase of x is -omega.
+fn section_continuous_phase<F: utils::Float>(coefficients: &Coefficients<F>, omega: F) -> F {
+    polynomial_continuous_phase([coefficients.b0, coefficients.b1, coefficients.b2], omega)
+        - polynomial_continuous_phase([F::ONE, coefficients.a1, coefficients.a2], omega)
+}
+
+fn polynomial_continuous_phase<F: utils::Float>(polynomial: [F; 3], omega: F) -> F {
+    let [p0, p1, p2] = polynomial;
+    let (leading_coefficient, degree) = if p2 != F::ZERO {
+        (p2, 2)
+    } else if p1 != F::ZERO {
+        (p1, 1)
+    } else if p0 != F::ZERO {
+        (p0, 0)
+    } else {
+        return F::ZERO;
+    };
+    let roots = utils::polynom_roots(p2, p1, p0);
+    // a double root is returned only once
+    let multiplicity = F::from(degree / roots.len().max(1)).unwrap();
+    let x = Complex::from_polar(F::ONE, -omega);
+    let mut phase = Complex::from(leading_coefficient).arg();
+    for root in roots {
+        let factor_phase = if root.norm() >= F::ONE {
+            (-root).arg() + (Complex::from(F::ONE) - x / root).arg()
+        } else {
+            -omega + (Complex::from(F::ONE) - root / x).arg()
+        };
+        phase += multiplicity * factor_phase;
+    }
+    phase
+}
+
 pub fn impulse_response<F: utils::Float>(
     process_function: &mut impl FnMut(F) -> F,
     eps: F,
@@ -72,15 +160,81 @@ pub fn impulse_response_for_coefficients<F: utils::Float>(
     impulse_response(&mut process, eps, hold_length, max_length)
 }
 
+/// Response to a unit step, until it stays within eps of final_value for hold_length samples.
+/// The samples after the first one within eps are dropped, like in impulse_response.
+pub fn step_response<F: utils::Float>(
+    process_function: &mut impl FnMut(F) -> F,
+    final_value: F,
+    eps: F,
+    hold_length: usize,
+    max_length: usize,
+) -> Vec<F> {
+    let mut response = Vec::new();
+    let mut eps_count = 0;
+    while eps_count <= hold_length && response.len() <= max_length {
+        let filter_out = process_function(F::ONE);
+        if (filter_out - final_value).abs() <= eps {
+            eps_count += 1;
+        } else {
+            eps_count = 0;
+        }
+        response.push(filter_out);
+    }
+    response.truncate(response.len() + 1 - eps_count.max(1));
+
+    response
+}
+
+pub fn step_response_for_coefficients<F: utils::Float>(
+    coefficients: Coefficients<F>,
+    eps: F,
+    hold_length: usize,
+    max_length: usize,
+) -> Vec<F> {
+    let final_value = dc_gain(&coefficients);
+    let mut filter = Filter::new(coefficients);
+    let mut process = move |s| filter.process(s);
+    step_response(&mut process, final_value, eps, hold_length, max_length)
+}
+
+/// Energy of the impulse response up to each sample relative to its total energy, see
+/// utils::cumulative_energy
+pub fn cumulative_energy_for_coefficients<F: utils::Float>(
+    coefficients: Coefficients<F>,
+    eps: F,
+    hold_length: usize,
+    max_length: usize,
+) -> Vec<F> {
+    utils::cumulative_energy(&impulse_response_for_coefficients(
+        coefficients,
+        eps,
+        hold_length,
+        max_length,
+    ))
+}
+
+/// Gain at frequency 0, where the step response settles
+pub fn dc_gain<F: utils::Float>(coefficients: &Coefficients<F>) -> F {
+    (coefficients.b0 + coefficients.b1 + coefficients.b2)
+        / (F::ONE + coefficients.a1 + coefficients.a2)
+}
+
+/// Bands are given as anything that converts into cascade coefficients, so single biquads
+/// and cascades of biquad sections can both be treated as one band each.
 pub mod multiband {
     use super::*;
 
-    pub fn make_frequency_response<F: utils::Float, C: IntoIterator<Item = Coefficients<F>>>(
+    pub fn make_frequency_response<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
         coefficients: C,
         sample_rate: F,
     ) -> impl Fn(F) -> Complex<F> {
         let transfer_functions = coefficients
             .into_iter()
+            .flat_map(|band| Into::<CascadeCoefficients<F>>::into(band).into_sections())
             .map(|c| make_transfer_function(c))
             .collect::<Vec<_>>();
         move |frequency| {
@@ -93,9 +247,69 @@ pub mod multiband {
         }
     }
 
+    /// Sum of the group delays of all sections in seconds, see super::make_group_delay_response
+    pub fn make_group_delay_response<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
+        coefficients: C,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        let sections = coefficients
+            .into_iter()
+            .flat_map(|band| Into::<CascadeCoefficients<F>>::into(band).into_sections())
+            .collect::<Vec<_>>();
+        move |frequency| {
+            sections
+                .iter()
+                .map(|section| section_group_delay(section, frequency, sample_rate))
+                .fold(F::ZERO, |sum, delay| sum + delay)
+        }
+    }
+
+    /// Phase in radians without jumps of 2 pi, unlike the argument of the frequency response.
+    /// It is derived from the roots of the transfer functions instead of unwrapping sampled
+    /// phases, so it doesn't depend on a frequency resolution. At frequency 0, the phase is
+    /// within [-pi, pi]. At zeros on the unit circle, it jumps by pi like the wrapped phase.
+    pub fn make_unwrapped_phase_response<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
+        coefficients: C,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        let sections = coefficients
+            .into_iter()
+            .flat_map(|band| Into::<CascadeCoefficients<F>>::into(band).into_sections())
+            .collect::<Vec<_>>();
+        let continuous_phase = move |omega| {
+            sections
+                .iter()
+                .map(|section| section_continuous_phase(section, omega))
+                .fold(F::ZERO, |sum, phase| sum + phase)
+        };
+        let offset = F::TWO_PI * (continuous_phase(F::ZERO) / F::TWO_PI).round();
+        move |frequency| continuous_phase(utils::omega(frequency, sample_rate)) - offset
+    }
+
+    /// Phase delay -phase / omega in seconds, based on the unwrapped phase
+    pub fn make_phase_delay_response<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
+        coefficients: C,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        utils::make_phase_delay_response(make_unwrapped_phase_response(coefficients, sample_rate))
+    }
+
     pub fn impulse_response_for_coefficients<
         F: utils::Float,
-        C: IntoIterator<Item = Coefficients<F>>,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
     >(
         coefficients: C,
         eps: F,
@@ -104,21 +318,160 @@ pub mod multiband {
     ) -> Vec<F> {
         let mut filters = coefficients
             .into_iter()
+            .flat_map(|band| Into::<CascadeCoefficients<F>>::into(band).into_sections())
             .map(|c| Filter::new(c))
             .collect::<Vec<_>>();
         let mut process = |s| process_sequential(&mut filters, s);
         super::impulse_response(&mut process, eps, hold_length, max_length)
     }
+
+    pub fn step_response_for_coefficients<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
+        coefficients: C,
+        eps: F,
+        hold_length: usize,
+        max_length: usize,
+    ) -> Vec<F> {
+        let sections = coefficients
+            .into_iter()
+            .flat_map(|band| Into::<CascadeCoefficients<F>>::into(band).into_sections())
+            .collect::<Vec<_>>();
+        let final_value = sections
+            .iter()
+            .fold(F::ONE, |product, section| product * dc_gain(section));
+        let mut filters = sections.into_iter().map(Filter::new).collect::<Vec<_>>();
+        let mut process = |s| process_sequential(&mut filters, s);
+        super::step_response(&mut process, final_value, eps, hold_length, max_length)
+    }
+
+    pub fn cumulative_energy_for_coefficients<
+        F: utils::Float,
+        B: Into<CascadeCoefficients<F>>,
+        C: IntoIterator<Item = B>,
+    >(
+        coefficients: C,
+        eps: F,
+        hold_length: usize,
+        max_length: usize,
+    ) -> Vec<F> {
+        utils::cumulative_energy(&impulse_response_for_coefficients(
+            coefficients,
+            eps,
+            hold_length,
+            max_length,
+        ))
+    }
+}
+
+/// Same as for single biquads, but for the sections of a cascade, that make up one band.
+pub mod cascade {
+    use super::*;
+
+    pub fn make_frequency_response<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        sample_rate: F,
+    ) -> impl Fn(F) -> Complex<F> {
+        multiband::make_frequency_response(coefficients.into_sections(), sample_rate)
+    }
+
+    pub fn make_group_delay_response<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        multiband::make_group_delay_response([coefficients], sample_rate)
+    }
+
+    pub fn make_unwrapped_phase_response<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        multiband::make_unwrapped_phase_response([coefficients], sample_rate)
+    }
+
+    pub fn make_phase_delay_response<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        sample_rate: F,
+    ) -> impl Fn(F) -> F {
+        multiband::make_phase_delay_response([coefficients], sample_rate)
+    }
+
+    pub fn impulse_response_for_coefficients<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        eps: F,
+        hold_length: usize,
+        max_length: usize,
+    ) -> Vec<F> {
+        let mut filter = crate::biquad::cascade::Filter::new(coefficients);
+        let mut process = move |s| filter.process(s);
+        impulse_response(&mut process, eps, hold_length, max_length)
+    }
+
+    pub fn step_response_for_coefficients<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        eps: F,
+        hold_length: usize,
+        max_length: usize,
+    ) -> Vec<F> {
+        multiband::step_response_for_coefficients([coefficients], eps, hold_length, max_length)
+    }
+
+    pub fn cumulative_energy_for_coefficients<F: utils::Float>(
+        coefficients: CascadeCoefficients<F>,
+        eps: F,
+        hold_length: usize,
+        max_length: usize,
+    ) -> Vec<F> {
+        utils::cumulative_energy(&impulse_response_for_coefficients(
+            coefficients,
+            eps,
+            hold_length,
+            max_length,
+        ))
+    }
+
+    pub fn zeros<F: utils::Float>(coefficients: &CascadeCoefficients<F>) -> Vec<Complex<F>> {
+        coefficients
+            .sections()
+            .iter()
+            .flat_map(super::zeros)
+            .collect()
+    }
+
+    pub fn poles<F: utils::Float>(coefficients: &CascadeCoefficients<F>) -> Vec<Complex<F>> {
+        coefficients
+            .sections()
+            .iter()
+            .flat_map(super::poles)
+            .collect()
+    }
+
+    pub fn is_stable<F: utils::Float>(coefficients: &CascadeCoefficients<F>) -> bool {
+        coefficients.sections().iter().all(super::is_stable)
+    }
 }
 
 pub fn zeros<F: utils::Float>(coefficients: &Coefficients<F>) -> utils::PolynomRoots<F> {
+    if is_first_order(coefficients) {
+        return utils::polynom_roots(F::ZERO, coefficients.b0, coefficients.b1);
+    }
     utils::polynom_roots(coefficients.b0, coefficients.b1, coefficients.b2)
 }
 
 pub fn poles<F: utils::Float>(coefficients: &Coefficients<F>) -> utils::PolynomRoots<F> {
+    if is_first_order(coefficients) {
+        return utils::polynom_roots(F::ZERO, F::ONE, coefficients.a1);
+    }
     utils::polynom_roots(F::ONE, coefficients.a1, coefficients.a2)
 }
 
+/// For first order sections, the pole and zero in the origin cancel each other, so we omit them
+pub fn is_first_order<F: utils::Float>(coefficients: &Coefficients<F>) -> bool {
+    coefficients.a2 == F::ZERO && coefficients.b2 == F::ZERO
+}
+
 pub fn is_stable<F: utils::Float>(coefficients: &Coefficients<F>) -> bool {
     let poles = poles(coefficients);
     poles
@@ -129,9 +482,11 @@ pub fn is_stable<F: utils::Float>(coefficients: &Coefficients<F>) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use crate::biquad::cascade;
+    use crate::eq;
     use crate::utils::amplitude_to_db;
     use assert_approx_eq::assert_approx_eq;
-    use more_asserts::assert_le;
+    use more_asserts::{assert_gt, assert_le, assert_lt};
     use num::complex::ComplexFloat;
 
     use super::*;
@@ -327,6 +682,59 @@ mod tests {
         assert_approx_eq!(gain_db_back, 0.0, 1e-3);
     }
 
+    #[test]
+    fn validate_peak_shapes() {
+        let sample_rate = 48000.0;
+        let frequency = 1000.0;
+        let q = 1.5;
+        let make_response = |peak_shape, gain_db| {
+            let eq = eq::Eq {
+                gain: eq::Gain::Db(gain_db),
+                frequency: eq::Frequency::Hz(frequency),
+                bandwidth: eq::Bandwidth::Q(q),
+                eq_type: eq::EqType::Peak,
+                slope: eq::Slope::default(),
+                alignment: eq::Alignment::default(),
+                design: eq::Design::default(),
+                peak_shape,
+            };
+            make_frequency_response(Coefficients::from_eq(&eq, sample_rate), sample_rate)
+        };
+
+        for peak_shape in eq::PeakShape::ALL {
+            for gain_db in [-12.0, -1.0, 1.0, 12.0] {
+                let response = make_response(*peak_shape, gain_db);
+                let inverse_response = make_response(*peak_shape, -gain_db);
+                assert_approx_eq!(amplitude_to_db(response(frequency).abs()), gain_db);
+                for i in 1..100 {
+                    let frequency = (i * 200) as f64;
+                    assert_approx_eq!(
+                        (response(frequency) * inverse_response(frequency)).abs(),
+                        1.0
+                    );
+                }
+            }
+        }
+
+        // fraction of the gain (in dB) left at the lower band edge
+        let (lower_edge, _) = cascade::band_edges(frequency, q);
+        let edge_fraction = |peak_shape, gain_db| {
+            amplitude_to_db(make_response(peak_shape, gain_db)(lower_edge).abs()) / gain_db
+        };
+        let cookbook_small = edge_fraction(eq::PeakShape::Cookbook, 1.0);
+        let cookbook_large = edge_fraction(eq::PeakShape::Cookbook, 12.0);
+        assert_approx_eq!(cookbook_small, 0.5, 1e-2);
+        assert_approx_eq!(cookbook_large, 0.5, 1e-2);
+        assert_lt!(
+            edge_fraction(eq::PeakShape::ProportionalQ, 12.0),
+            edge_fraction(eq::PeakShape::ProportionalQ, 1.0)
+        );
+        assert_gt!(
+            edge_fraction(eq::PeakShape::ConstantQ, 12.0),
+            edge_fraction(eq::PeakShape::ConstantQ, 1.0)
+        );
+    }
+
     #[test]
     fn validate_lowshelf() {
         let sample_rate = 48000.0;
@@ -357,6 +765,76 @@ mod tests {
         assert_approx_eq!(gain_db_back, gain_db, 1e-4);
     }
 
+    #[test]
+    fn validate_first_order_lowpass_and_highpass() {
+        let sample_rate = 48000.0;
+        let frequency = 1000.0;
+        let lowpass = make_frequency_response(
+            Coefficients::from_first_order_lowpass(frequency, sample_rate),
+            sample_rate,
+        );
+        let highpass = make_frequency_response(
+            Coefficients::from_first_order_highpass(frequency, sample_rate),
+            sample_rate,
+        );
+
+        assert_approx_eq!(amplitude_to_db(lowpass(frequency).abs()), -3.0103, 1e-3);
+        assert_approx_eq!(amplitude_to_db(highpass(frequency).abs()), -3.0103, 1e-3);
+        assert_approx_eq!(amplitude_to_db(lowpass(10.0).abs()), 0.0, 1e-3);
+        assert_approx_eq!(amplitude_to_db(highpass(20000.0).abs()), 0.0, 1e-2);
+        assert_le!(amplitude_to_db(lowpass(20000.0).abs()), -25.0);
+        assert_le!(amplitude_to_db(highpass(10.0).abs()), -35.0);
+    }
+
+    #[test]
+    fn validate_first_order_allpass() {
+        let sample_rate = 48000.0;
+        let frequency = 3000.0;
+        let coefficients = Coefficients::from_first_order_allpass(frequency, sample_rate);
+        let calc_response = make_frequency_response(coefficients, sample_rate);
+
+        for test_frequency in [10.0, frequency, 20000.0] {
+            assert_approx_eq!(calc_response(test_frequency).abs(), 1.0);
+        }
+        assert_approx_eq!(calc_response(0.0).arg(), 0.0);
+        assert_approx_eq!(
+            calc_response(frequency).arg().abs(),
+            std::f64::consts::FRAC_PI_2
+        );
+    }
+
+    #[test]
+    fn validate_first_order_shelves() {
+        let sample_rate = 48000.0;
+        let frequency = 1000.0;
+        let gain_db = -7.3;
+        let lowshelf = make_frequency_response(
+            Coefficients::from_first_order_lowshelf_db(gain_db, frequency, sample_rate),
+            sample_rate,
+        );
+        let highshelf = make_frequency_response(
+            Coefficients::from_first_order_highshelf_db(gain_db, frequency, sample_rate),
+            sample_rate,
+        );
+
+        assert_approx_eq!(amplitude_to_db(lowshelf(0.0).abs()), gain_db);
+        assert_approx_eq!(amplitude_to_db(lowshelf(frequency).abs()), 0.5 * gain_db);
+        assert_approx_eq!(amplitude_to_db(lowshelf(24000.0).abs()), 0.0);
+
+        assert_approx_eq!(amplitude_to_db(highshelf(0.0).abs()), 0.0);
+        assert_approx_eq!(amplitude_to_db(highshelf(frequency).abs()), 0.5 * gain_db);
+        assert_approx_eq!(amplitude_to_db(highshelf(24000.0).abs()), gain_db);
+    }
+
+    #[test]
+    fn first_order_sections_have_single_roots() {
+        let coefficients = Coefficients::from_first_order_lowshelf_db(6.0, 500.0, 44100.0);
+        assert!(is_first_order(&coefficients));
+        assert_eq!(zeros(&coefficients).len(), 1);
+        assert_eq!(poles(&coefficients).len(), 1);
+        assert!(is_stable(&coefficients));
+    }
+
     #[test]
     fn validate_transfer_function_multiband() {
         let sample_rate = 44100.0;
@@ -382,4 +860,136 @@ mod tests {
             assert_approx_eq!(r0, r1);
         }
     }
+
+    #[test]
+    fn delays_of_symmetric_fir() {
+        let sample_rate = 48000.0;
+        let coefficients = Coefficients {
+            a1: 0.0,
+            a2: 0.0,
+            b0: 0.25,
+            b1: 0.5,
+            b2: 0.25,
+        };
+        let group_delay = make_group_delay_response(coefficients.clone(), sample_rate);
+        let phase_delay = make_phase_delay_response(coefficients, sample_rate);
+        for frequency in [10.0, 1000.0, 20000.0] {
+            assert_approx_eq!(group_delay(frequency) * sample_rate, 1.0);
+            assert_approx_eq!(phase_delay(frequency) * sample_rate, 1.0);
+        }
+    }
+
+    #[test]
+    fn unwrapped_phase_of_allpass() {
+        let sample_rate = 48000.0;
+        let phase = make_unwrapped_phase_response(
+            Coefficients::from_allpass(1000.0, 0.7, sample_rate),
+            sample_rate,
+        );
+        assert_approx_eq!(phase(0.0), 0.0);
+        assert_approx_eq!(phase(1000.0), -std::f64::consts::PI);
+        assert_approx_eq!(phase(0.5 * sample_rate), -2.0 * std::f64::consts::PI);
+        // without the jump, where the wrapped phase goes from -pi to pi
+        assert_lt!(phase(1010.0), -std::f64::consts::PI);
+    }
+
+    #[test]
+    fn analytic_delays_match_the_phase() {
+        let sample_rate = 48000.0;
+        let eqs = [
+            (eq::EqType::LowPass, eq::Slope::Db12),
+            (eq::EqType::HighPass, eq::Slope::Db48),
+            (eq::EqType::Peak, eq::Slope::Db12),
+            (eq::EqType::LowShelf, eq::Slope::Db12),
+            (eq::EqType::Notch, eq::Slope::Db12),
+        ]
+        .map(|(eq_type, slope)| {
+            cascade::Coefficients::from_eq(
+                &eq::Eq {
+                    gain: eq::Gain::Db(-9.0),
+                    frequency: eq::Frequency::Hz(800.0),
+                    bandwidth: eq::Bandwidth::Q(1.5),
+                    eq_type,
+                    slope,
+                    alignment: eq::Alignment::Butterworth,
+                    design: eq::Design::Cookbook,
+                    peak_shape: eq::PeakShape::default(),
+                },
+                sample_rate,
+            )
+        });
+        for coefficients in eqs.iter().map(|c| vec![c.clone()]).chain([eqs.to_vec()]) {
+            let frequency_response =
+                multiband::make_frequency_response(coefficients.clone(), sample_rate);
+            let phase = multiband::make_unwrapped_phase_response(coefficients.clone(), sample_rate);
+            let group_delay =
+                multiband::make_group_delay_response(coefficients.clone(), sample_rate);
+            let phase_delay = multiband::make_phase_delay_response(coefficients, sample_rate);
+            for frequency in [30.0, 300.0, 700.0, 1000.0, 5000.0, 18000.0] {
+                let wrapped_difference = phase(frequency) - frequency_response(frequency).arg();
+                let turns = wrapped_difference / std::f64::consts::TAU;
+                assert_approx_eq!(turns, turns.round(), 1e-9);
+
+                let step = 1e-3;
+                let derivative = (phase(frequency + step) - phase(frequency - step))
+                    / (std::f64::consts::TAU * 2.0 * step);
+                assert_approx_eq!(group_delay(frequency), -derivative, 1e-9);
+
+                assert_approx_eq!(
+                    phase_delay(frequency),
+                    -phase(frequency) / (std::f64::consts::TAU * frequency)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn step_response_is_the_running_sum_of_the_impulse_response() {
+        let sample_rate = 48000.0;
+        let bands = [
+            cascade::Coefficients::from_lowpass(
+                2000.0,
+                1.0,
+                eq::Slope::Db24,
+                eq::Alignment::Butterworth,
+                sample_rate,
+            ),
+            cascade::Coefficients::from(Coefficients::from_peak_db(6.0, 300.0, 2.0, sample_rate)),
+        ];
+        let impulse_response =
+            multiband::impulse_response_for_coefficients(bands.clone(), 1e-12, 10, 10000);
+        let step_response = multiband::step_response_for_coefficients(bands, 1e-12, 10, 10000);
+        let mut running_sum = 0.0;
+        for (impulse, step) in impulse_response.iter().zip(step_response.iter()) {
+            running_sum += impulse;
+            assert_approx_eq!(running_sum, step, 1e-12);
+        }
+        assert_approx_eq!(*step_response.last().unwrap(), 1.0, 1e-12);
+
+        let highpass = Coefficients::from_highpass(100.0, 0.7, sample_rate);
+        let step_response = step_response_for_coefficients(highpass.clone(), 1e-6, 10, 10000);
+        assert_approx_eq!(step_response[0], highpass.b0);
+        assert_lt!(step_response.last().unwrap().abs(), 1e-6);
+        assert_lt!(step_response.len(), 10000);
+    }
+
+    #[test]
+    fn high_q_notches_ring_longer() {
+        let sample_rate = 48000.0;
+        let samples_until_settled = |q| {
+            let cumulative_energy = cumulative_energy_for_coefficients(
+                Coefficients::from_notch(1000.0, q, sample_rate),
+                1e-12,
+                10,
+                48000,
+            );
+            assert_approx_eq!(*cumulative_energy.last().unwrap(), 1.0);
+            assert!(cumulative_energy.windows(2).all(|pair| pair[0] <= pair[1]));
+            cumulative_energy
+                .iter()
+                .position(|energy| *energy >= 0.9999)
+                .unwrap()
+        };
+        assert_gt!(samples_until_settled(20.0), 10 * samples_until_settled(0.7));
+    }
 }
@@ -0,0 +1,319 @@
+use crate::utils;
+use num::Complex;
+use std::sync::Arc;
+
+/// Spectra of the kernel partitions for the Convolver. Building them needs FFTs and allocations,
+/// so this is meant to happen off the audio thread.
+#[derive(Debug, Clone)]
+pub struct Kernel<F: utils::Float> {
+    block_length: usize,
+    partitions: Vec<Vec<Complex<F>>>,
+}
+
+impl<F: utils::Float + rustfft::FftNum> Kernel<F> {
+    pub fn new(taps: &[F], block_length: usize) -> Self {
+        assert!(utils::is_power_of_two(block_length));
+        let fft_length = 2 * block_length;
+        let fft = rustfft::FftPlanner::<F>::new().plan_fft_forward(fft_length);
+        let partitions = taps
+            .chunks(block_length)
+            .map(|chunk| {
+                let mut spectrum = vec![Complex::from(F::ZERO); fft_length];
+                for (value, tap) in spectrum.iter_mut().zip(chunk) {
+                    *value = Complex::from(*tap);
+                }
+                fft.process(&mut spectrum);
+                spectrum
+            })
+            .collect::<Vec<_>>();
+        Self {
+            block_length,
+            partitions,
+        }
+    }
+
+    /// A single tap of one, which passes the signal through with the latency of the convolver
+    pub fn passthrough(block_length: usize, num_partitions: usize) -> Self {
+        let mut taps = vec![F::ZERO; num_partitions * block_length];
+        taps[0] = F::ONE;
+        Self::new(&taps, block_length)
+    }
+
+    pub fn block_length(&self) -> usize {
+        self.block_length
+    }
+
+    pub fn num_partitions(&self) -> usize {
+        self.partitions.len()
+    }
+}
+
+/// Uniformly partitioned overlap-save convolution: the kernel is split into partitions of
+/// block_length taps, and every block of input is transformed once and kept in a frequency
+/// domain delay line, where it is multiplied with the spectra of all partitions. The latency is
+/// block_length samples, independent of the kernel length. Processing doesn't allocate.
+pub struct Convolver<F: utils::Float> {
+    forward_fft: Arc<dyn rustfft::Fft<F>>,
+    inverse_fft: Arc<dyn rustfft::Fft<F>>,
+    scratch: Vec<Complex<F>>,
+    kernel: Kernel<F>,
+    /// Replaces kernel with a crossfade over the next block, if pending
+    next_kernel: Kernel<F>,
+    crossfade_pending: bool,
+    /// Spectra of the most recent input blocks, one per partition
+    input_spectra: Vec<Vec<Complex<F>>>,
+    newest_spectrum: usize,
+    /// The last two input blocks
+    input_blocks: Vec<F>,
+    output_block: Vec<F>,
+    block_position: usize,
+    fft_buffer: Vec<Complex<F>>,
+    next_fft_buffer: Vec<Complex<F>>,
+}
+
+impl<F: utils::Float + rustfft::FftNum> Convolver<F> {
+    pub fn new(kernel: Kernel<F>) -> Self {
+        let block_length = kernel.block_length();
+        let fft_length = 2 * block_length;
+        let mut planner = rustfft::FftPlanner::<F>::new();
+        let forward_fft = planner.plan_fft_forward(fft_length);
+        let inverse_fft = planner.plan_fft_inverse(fft_length);
+        let scratch_length = forward_fft
+            .get_inplace_scratch_len()
+            .max(inverse_fft.get_inplace_scratch_len());
+        let zero = Complex::from(F::ZERO);
+        Self {
+            forward_fft,
+            inverse_fft,
+            scratch: vec![zero; scratch_length],
+            input_spectra: vec![vec![zero; fft_length]; kernel.num_partitions()],
+            newest_spectrum: 0,
+            input_blocks: vec![F::ZERO; fft_length],
+            output_block: vec![F::ZERO; block_length],
+            block_position: 0,
+            fft_buffer: vec![zero; fft_length],
+            next_fft_buffer: vec![zero; fft_length],
+            next_kernel: kernel.clone(),
+            crossfade_pending: false,
+            kernel,
+        }
+    }
+
+    pub fn latency(&self) -> usize {
+        self.kernel.block_length()
+    }
+
+    /// Takes over the kernel at the next block, crossfading from the current one. The kernel
+    /// must have the block length and number of partitions of the current kernel, then copying
+    /// it doesn't allocate.
+    pub fn set_kernel(&mut self, kernel: &Kernel<F>) {
+        assert_eq!(kernel.block_length(), self.kernel.block_length());
+        assert_eq!(kernel.num_partitions(), self.kernel.num_partitions());
+        for (partition, new_partition) in self
+            .next_kernel
+            .partitions
+            .iter_mut()
+            .zip(kernel.partitions.iter())
+        {
+            partition.copy_from_slice(new_partition);
+        }
+        self.crossfade_pending = true;
+    }
+
+    pub fn reset(&mut self) {
+        let zero = Complex::from(F::ZERO);
+        for spectrum in self.input_spectra.iter_mut() {
+            spectrum.fill(zero);
+        }
+        self.input_blocks.fill(F::ZERO);
+        self.output_block.fill(F::ZERO);
+        self.block_position = 0;
+        if self.crossfade_pending {
+            std::mem::swap(&mut self.kernel, &mut self.next_kernel);
+            self.crossfade_pending = false;
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [F]) {
+        let block_length = self.kernel.block_length();
+        for sample in samples.iter_mut() {
+            let input = *sample;
+            *sample = self.output_block[self.block_position];
+            self.input_blocks[block_length + self.block_position] = input;
+            self.block_position += 1;
+            if self.block_position == block_length {
+                self.process_block();
+                self.block_position = 0;
+            }
+        }
+    }
+
+    fn process_block(&mut self) {
+        let block_length = self.kernel.block_length();
+        let num_partitions = self.input_spectra.len();
+        self.newest_spectrum = (self.newest_spectrum + 1) % num_partitions;
+        let input_spectrum = &mut self.input_spectra[self.newest_spectrum];
+        for (value, sample) in input_spectrum.iter_mut().zip(self.input_blocks.iter()) {
+            *value = Complex::from(*sample);
+        }
+        self.forward_fft
+            .process_with_scratch(input_spectrum, &mut self.scratch);
+        self.input_blocks.copy_within(block_length.., 0);
+
+        multiply_accumulate(
+            &self.input_spectra,
+            self.newest_spectrum,
+            &self.kernel,
+            &mut self.fft_buffer,
+        );
+        self.inverse_fft
+            .process_with_scratch(&mut self.fft_buffer, &mut self.scratch);
+        // the first half is circular convolution garbage, as usual with overlap-save
+        let scale = F::ONE / F::from(2 * block_length).unwrap();
+        for (output, value) in self
+            .output_block
+            .iter_mut()
+            .zip(self.fft_buffer[block_length..].iter())
+        {
+            *output = value.re * scale;
+        }
+
+        if self.crossfade_pending {
+            multiply_accumulate(
+                &self.input_spectra,
+                self.newest_spectrum,
+                &self.next_kernel,
+                &mut self.next_fft_buffer,
+            );
+            self.inverse_fft
+                .process_with_scratch(&mut self.next_fft_buffer, &mut self.scratch);
+            let step = F::ONE / F::from(block_length).unwrap();
+            for (index, (output, value)) in self
+                .output_block
+                .iter_mut()
+                .zip(self.next_fft_buffer[block_length..].iter())
+                .enumerate()
+            {
+                let fade_in = F::from(index + 1).unwrap() * step;
+                *output = *output * (F::ONE - fade_in) + value.re * scale * fade_in;
+            }
+            // the previous kernel stays allocated for the next change
+            std::mem::swap(&mut self.kernel, &mut self.next_kernel);
+            self.crossfade_pending = false;
+        }
+    }
+}
+
+fn multiply_accumulate<F: utils::Float>(
+    input_spectra: &[Vec<Complex<F>>],
+    newest_spectrum: usize,
+    kernel: &Kernel<F>,
+    accumulated: &mut [Complex<F>],
+) {
+    accumulated.fill(Complex::from(F::ZERO));
+    let num_partitions = input_spectra.len();
+    for (delay, partition) in kernel.partitions.iter().enumerate() {
+        let input_spectrum =
+            &input_spectra[(newest_spectrum + num_partitions - delay) % num_partitions];
+        for ((sum, input), coefficient) in accumulated
+            .iter_mut()
+            .zip(input_spectrum.iter())
+            .zip(partition.iter())
+        {
+            *sum = *sum + *input * *coefficient;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn make_signal(length: usize) -> Vec<f64> {
+        (0..length)
+            .map(|n| ((n * 17) % 23) as f64 / 23.0 - 0.5)
+            .collect()
+    }
+
+    fn convolve(signal: &[f64], taps: &[f64]) -> Vec<f64> {
+        (0..signal.len())
+            .map(|n| {
+                taps.iter()
+                    .enumerate()
+                    .take(n + 1)
+                    .map(|(k, tap)| tap * signal[n - k])
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn process_in_chunks(convolver: &mut Convolver<f64>, signal: &[f64]) -> Vec<f64> {
+        let mut output = signal.to_vec();
+        let mut start = 0;
+        for chunk_length in [1, 100, 7, 64, 300].iter().cycle() {
+            if start >= output.len() {
+                break;
+            }
+            let end = (start + chunk_length).min(output.len());
+            convolver.process(&mut output[start..end]);
+            start = end;
+        }
+        output
+    }
+
+    #[test]
+    fn matches_direct_convolution() {
+        let block_length = 64;
+        // not a multiple of the block length
+        let taps = make_signal(300).iter().rev().copied().collect::<Vec<_>>();
+        let signal = make_signal(2000);
+        let mut convolver = Convolver::new(Kernel::new(&taps, block_length));
+        assert_eq!(convolver.latency(), block_length);
+
+        let output = process_in_chunks(&mut convolver, &signal);
+        let expected = convolve(&signal, &taps);
+        for (actual, expected) in output[block_length..].iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-12);
+        }
+        for sample in output[..block_length].iter() {
+            assert_eq!(*sample, 0.0);
+        }
+
+        convolver.reset();
+        let output_after_reset = process_in_chunks(&mut convolver, &signal);
+        assert_eq!(output_after_reset, output);
+    }
+
+    #[test]
+    fn kernel_changes_are_crossfaded() {
+        let block_length = 32;
+        let taps = make_signal(100);
+        let new_taps = taps.iter().map(|tap| -0.5 * tap).collect::<Vec<_>>();
+        let signal = make_signal(1000);
+        let mut convolver = Convolver::new(Kernel::new(&taps, block_length));
+
+        let change_position = 10 * block_length + 5;
+        let mut output = signal.clone();
+        convolver.process(&mut output[..change_position]);
+        convolver.set_kernel(&Kernel::new(&new_taps, block_length));
+        convolver.process(&mut output[change_position..]);
+
+        // the kernels are crossfaded in the output of the block the change happened in
+        let crossfade_start = 11 * block_length;
+        let crossfade_end = crossfade_start + block_length;
+        let expected = convolve(&signal, &taps);
+        let new_expected = convolve(&signal, &new_taps);
+        let delayed = |n: usize| n - block_length;
+        for n in block_length..crossfade_start {
+            assert_approx_eq!(output[n], expected[delayed(n)], 1e-12);
+        }
+        for n in crossfade_start..crossfade_end {
+            let (old, new) = (expected[delayed(n)], new_expected[delayed(n)]);
+            assert!(output[n] >= old.min(new) - 1e-12 && output[n] <= old.max(new) + 1e-12);
+        }
+        for n in crossfade_end..signal.len() {
+            assert_approx_eq!(output[n], new_expected[delayed(n)], 1e-12);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::biquad::{coefficients, filter};
+use crate::*;
+
+/// Settings of a dynamic band. The level of the detector above the threshold moves the gain of
+/// the band like a compressor moves the level: ratios above one lower the gain, ratios below one
+/// raise it. The change is limited to range_db. With sidechain, the detector listens to an
+/// external signal instead of the one the band processes.
+#[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(bound = "F: utils::Float")]
+pub struct Dynamics<F: utils::Float> {
+    pub enabled: bool,
+    #[serde(default)]
+    pub sidechain: bool,
+    pub threshold_db: F,
+    pub ratio: F,
+    pub range_db: F,
+    pub attack_ms: F,
+    pub release_ms: F,
+}
+
+impl<F: utils::Float> Default for Dynamics<F> {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sidechain: false,
+            threshold_db: F::from(-20).unwrap(),
+            ratio: F::TWO,
+            range_db: F::from(12).unwrap(),
+            attack_ms: F::TEN,
+            release_ms: F::from(100).unwrap(),
+        }
+    }
+}
+
+impl<F: utils::Float> Dynamics<F> {
+    /// Whether the dynamics apply to the eq, which needs a gain
+    pub fn is_active(&self, eq: &eq::Eq<F>) -> bool {
+        self.enabled && eq.eq_type.has_gain_db()
+    }
+
+    /// Change of the band gain in dB for the detector level
+    pub fn gain_change_db(&self, level_db: F) -> F {
+        let overshoot_db = (level_db - self.threshold_db).max(F::ZERO);
+        let gain_change_db = overshoot_db * (F::ONE / self.ratio - F::ONE);
+        gain_change_db.clamp(-self.range_db, self.range_db)
+    }
+
+    /// The eq with its gain moved by gain_change_db, if the dynamics are active
+    pub fn apply(&self, eq: &eq::Eq<F>, gain_change_db: F) -> eq::Eq<F> {
+        let mut dynamic_eq = eq.clone();
+        if self.is_active(eq) {
+            dynamic_eq.gain = eq::Gain::Db(eq.gain.db() + gain_change_db);
+        }
+        dynamic_eq
+    }
+
+    pub fn envelope_coefficients(&self, sample_rate: F) -> envelope_follower::Coefficients<F> {
+        let milliseconds = F::from(1000).unwrap();
+        envelope_follower::Coefficients::from_attack_and_release_time(
+            self.attack_ms / milliseconds,
+            self.release_ms / milliseconds,
+            sample_rate,
+        )
+    }
+}
+
+/// Level of the signal around the frequency of a band: a band-pass with the frequency and
+/// bandwidth of the band (see eq::Eq::to_band_pass), followed by a peak envelope. The channels
+/// are linked, the loudest one drives the envelope.
+pub struct Detector<F: utils::Float> {
+    band_pass_eq: eq::Eq<F>,
+    coefficients: coefficients::Coefficients<F>,
+    states: Vec<filter::State<F>>,
+    envelope: envelope_follower::EnvelopeFollower<F>,
+}
+
+impl<F: utils::Float> Detector<F> {
+    pub fn new(num_channels: usize) -> Self {
+        Self {
+            band_pass_eq: eq::Eq {
+                gain: eq::Gain::Db(F::ZERO),
+                frequency: eq::Frequency::Hz(F::ZERO),
+                bandwidth: eq::Bandwidth::Q(F::ZERO),
+                eq_type: eq::EqType::BandPass,
+                slope: eq::Slope::default(),
+                alignment: eq::Alignment::default(),
+                design: eq::Design::Cookbook,
+                peak_shape: eq::PeakShape::default(),
+            },
+            coefficients: coefficients::Coefficients::muted(),
+            states: (0..num_channels).map(|_| filter::State::new()).collect(),
+            envelope: envelope_follower::EnvelopeFollower::from_coefficients(
+                &envelope_follower::Coefficients {
+                    attack: F::ONE,
+                    release: F::ONE,
+                },
+            ),
+        }
+    }
+
+    /// Redesigns the band-pass if the frequency or bandwidth of the eq changed. If the band-pass
+    /// can't be designed, the previous one stays in use.
+    pub fn set_band(&mut self, eq: &eq::Eq<F>, sample_rate: F) {
+        let band_pass_eq = eq.to_band_pass();
+        if band_pass_eq != self.band_pass_eq
+            && let Ok(coefficients) =
+                coefficients::Coefficients::try_from_eq(&band_pass_eq, sample_rate)
+        {
+            self.coefficients = coefficients;
+            self.band_pass_eq = band_pass_eq;
+        }
+    }
+
+    pub fn set_dynamics(&mut self, dynamics: &Dynamics<F>, sample_rate: F) {
+        self.envelope
+            .set_coefficients(&dynamics.envelope_coefficients(sample_rate));
+    }
+
+    /// Processes one sample of every channel, returns the level in dB
+    pub fn process(&mut self, frame: &[F]) -> F {
+        let mut peak = F::ZERO;
+        for (state, sample) in self.states.iter_mut().zip(frame) {
+            peak = peak.max(state.process(&self.coefficients, *sample).abs());
+        }
+        utils::amplitude_to_db(self.envelope.process(peak))
+    }
+
+    /// The level in dB after the last processed frame
+    pub fn level_db(&self) -> F {
+        utils::amplitude_to_db(self.envelope.value())
+    }
+
+    pub fn reset(&mut self) {
+        for state in self.states.iter_mut() {
+            state.reset();
+        }
+        self.envelope.reset(F::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn make_peak(frequency: f64) -> eq::Eq<f64> {
+        eq::Eq {
+            gain: eq::Gain::Db(6.0),
+            frequency: eq::Frequency::Hz(frequency),
+            bandwidth: eq::Bandwidth::Q(2.0),
+            eq_type: eq::EqType::Peak,
+            slope: eq::Slope::default(),
+            alignment: eq::Alignment::default(),
+            design: eq::Design::Cookbook,
+            peak_shape: eq::PeakShape::default(),
+        }
+    }
+
+    #[test]
+    fn gain_change_follows_the_ratio() {
+        let dynamics = Dynamics::<f64> {
+            enabled: true,
+            threshold_db: -20.0,
+            ratio: 4.0,
+            range_db: 12.0,
+            ..Dynamics::default()
+        };
+        assert_eq!(dynamics.gain_change_db(-30.0), 0.0);
+        assert_approx_eq!(dynamics.gain_change_db(-12.0), -6.0);
+        // limited by the range
+        assert_approx_eq!(dynamics.gain_change_db(0.0), -12.0);
+
+        let expanding = Dynamics {
+            ratio: 0.5,
+            ..dynamics
+        };
+        assert_approx_eq!(expanding.gain_change_db(-16.0), 4.0);
+
+        let eq = make_peak(1000.0);
+        assert_approx_eq!(dynamics.apply(&eq, -6.0).gain.db(), 0.0);
+        let mut band_pass = eq.clone();
+        band_pass.eq_type = eq::EqType::BandPass;
+        assert_eq!(dynamics.apply(&band_pass, -6.0), band_pass);
+    }
+
+    #[test]
+    fn detector_listens_at_the_band_frequency() {
+        let sample_rate = 48000.0;
+        let dynamics = Dynamics {
+            attack_ms: 0.1,
+            release_ms: 100.0,
+            ..Dynamics::default()
+        };
+        let level_db = |signal_frequency: f64| {
+            let mut detector = Detector::new(2);
+            detector.set_band(&make_peak(1000.0), sample_rate);
+            detector.set_dynamics(&dynamics, sample_rate);
+            let mut max_level_db = f64::NEG_INFINITY;
+            for n in 0..4800 {
+                let sample = (utils::omega(signal_frequency, sample_rate) * n as f64).sin() * 0.5;
+                // the louder channel drives the detector
+                let level_db = detector.process(&[0.5 * sample, sample]);
+                max_level_db = max_level_db.max(level_db);
+            }
+            max_level_db
+        };
+        assert_approx_eq!(level_db(1000.0), utils::amplitude_to_db(0.5), 0.5);
+        assert!(level_db(100.0) < utils::amplitude_to_db(0.5) - 12.0);
+        assert!(level_db(10000.0) < utils::amplitude_to_db(0.5) - 12.0);
+    }
+}
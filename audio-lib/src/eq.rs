@@ -1,5 +1,6 @@
 use crate::utils;
 use enum_table::Enumable;
+use std::ops::RangeInclusive;
 
 #[derive(
     Debug, PartialEq, Clone, Copy, enum_table::Enumable, serde::Serialize, serde::Deserialize,
@@ -15,6 +16,14 @@ pub enum EqType {
     LowShelf,
     HighShelf,
     Bypassed,
+    // appended, since the plugin stores eq types by index
+    Tilt,
+    BandShelf,
+    FirstOrderLowPass,
+    FirstOrderHighPass,
+    FirstOrderAllPass,
+    FirstOrderLowShelf,
+    FirstOrderHighShelf,
 }
 
 impl EqType {
@@ -32,6 +41,13 @@ impl EqType {
         "Low Shelf",
         "High Shelf",
         "Bypassed",
+        "Tilt",
+        "Band Shelf",
+        "Low Pass (1st Order)",
+        "High Pass (1st Order)",
+        "AllPass (1st Order)",
+        "Low Shelf (1st Order)",
+        "High Shelf (1st Order)",
     ];
     pub fn to_string(&self) -> &str {
         Self::ALL_NAMES[*self as usize]
@@ -58,6 +74,10 @@ impl EqType {
             EqType::Peak => true,
             EqType::LowShelf => true,
             EqType::HighShelf => true,
+            EqType::Tilt => true,
+            EqType::BandShelf => true,
+            EqType::FirstOrderLowShelf => true,
+            EqType::FirstOrderHighShelf => true,
             _ => false,
         }
     }
@@ -65,10 +85,39 @@ impl EqType {
     pub const fn has_q(&self) -> bool {
         match self {
             EqType::Volume => false,
+            EqType::Tilt => false,
+            EqType::FirstOrderLowPass => false,
+            EqType::FirstOrderHighPass => false,
+            EqType::FirstOrderAllPass => false,
+            EqType::FirstOrderLowShelf => false,
+            EqType::FirstOrderHighShelf => false,
             EqType::Bypassed => false,
             _ => true,
         }
     }
+
+    pub const fn has_bandwidth_unit(&self, unit: BandwidthUnit) -> bool {
+        match unit {
+            BandwidthUnit::Q => self.has_q(),
+            BandwidthUnit::Octaves => matches!(
+                self,
+                EqType::BandPass
+                    | EqType::AllPass
+                    | EqType::Notch
+                    | EqType::Peak
+                    | EqType::BandShelf
+            ),
+            BandwidthUnit::ShelfSlope => matches!(self, EqType::LowShelf | EqType::HighShelf),
+        }
+    }
+
+    pub const fn has_peak_shape(&self) -> bool {
+        matches!(self, EqType::Peak)
+    }
+
+    pub const fn has_slope(&self) -> bool {
+        matches!(self, EqType::LowPass | EqType::HighPass)
+    }
 }
 
 impl TryFrom<usize> for EqType {
@@ -95,6 +144,281 @@ impl TryFrom<&str> for EqType {
     }
 }
 
+/// Steepness of low pass and high pass bands. Everything steeper than 12 dB/oct
+/// is realized as a cascade of biquad sections (plus a first order section for odd orders).
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Slope {
+    Db6,
+    #[default]
+    Db12,
+    Db18,
+    Db24,
+    Db36,
+    Db48,
+}
+
+impl Slope {
+    pub const ALL: &'static [Slope] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = [
+        "6 dB/oct",
+        "12 dB/oct",
+        "18 dB/oct",
+        "24 dB/oct",
+        "36 dB/oct",
+        "48 dB/oct",
+    ];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+
+    pub const fn order(&self) -> usize {
+        match self {
+            Slope::Db6 => 1,
+            Slope::Db12 => 2,
+            Slope::Db18 => 3,
+            Slope::Db24 => 4,
+            Slope::Db36 => 6,
+            Slope::Db48 => 8,
+        }
+    }
+}
+
+impl TryFrom<usize> for Slope {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("Slope for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for Slope {
+    type Error = &'static str;
+
+    fn try_from(slope_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES.iter().position(|&name| name == slope_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("Slope {} is not defined", value)),
+        }
+    }
+}
+
+/// Alignment of the cascaded sections of low pass and high pass bands.
+/// Butterworth is maximally flat (-3 dB at the cutoff frequency), Linkwitz-Riley is a squared
+/// Butterworth of half the order (-6 dB at the cutoff frequency), so that low pass and high pass
+/// sum up flat. Linkwitz-Riley only exists for even orders, the odd ones (Db6 and Db18) fall
+/// back to Butterworth, see for_slope. q sets the resonance of the first squared Butterworth
+/// only, so the peak is the same as for Butterworth.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Alignment {
+    #[default]
+    Butterworth,
+    LinkwitzRiley,
+}
+
+impl Alignment {
+    pub const ALL: &'static [Alignment] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = ["Butterworth", "Linkwitz-Riley"];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+
+    /// The alignment the cascade of the slope gets
+    pub const fn for_slope(&self, slope: Slope) -> Alignment {
+        match self {
+            Alignment::LinkwitzRiley if slope.order().is_multiple_of(2) => Alignment::LinkwitzRiley,
+            _ => Alignment::Butterworth,
+        }
+    }
+}
+
+impl TryFrom<usize> for Alignment {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("Alignment for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for Alignment {
+    type Error = &'static str;
+
+    fn try_from(alignment_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES
+            .iter()
+            .position(|&name| name == alignment_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("Alignment {} is not defined", value)),
+        }
+    }
+}
+
+/// How the digital coefficients of a band are derived from its analog prototype
+/// (see biquad::analog).
+/// Cookbook is the bilinear transform with prewarping at the band frequency, in the closed form
+/// formulas of the Audio EQ Cookbook, which compress the response towards Nyquist
+/// ("cramping"). AnalogMatched maps the poles exactly and fits the zeros to the analog
+/// magnitude response (after Vicanek, "Matched Second Order Digital Filters"), which keeps
+/// peaks and shelves close to Nyquist at their analog shape.
+/// The remaining designs discretise the analog prototype with a classic transform:
+/// MatchedZ (poles and zeros mapped with z = exp(s*T)) and ImpulseInvariant (sampled
+/// impulse response, which aliases).
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Design {
+    #[default]
+    Cookbook,
+    AnalogMatched,
+    MatchedZ,
+    ImpulseInvariant,
+}
+
+impl Design {
+    pub const ALL: &'static [Design] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = [
+        "Cookbook",
+        "Analog Matched",
+        "Matched Z",
+        "Impulse Invariant",
+    ];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+}
+
+impl TryFrom<usize> for Design {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("Design for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for Design {
+    type Error = &'static str;
+
+    fn try_from(design_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES.iter().position(|&name| name == design_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("Design {} is not defined", value)),
+        }
+    }
+}
+
+/// How the bandwidth of a peak depends on its gain. All shapes keep boost and cut reciprocal.
+/// Cookbook keeps the bandwidth between the half gain (in dB) points at q. ProportionalQ
+/// narrows the peak with growing gain, so small gains get a wide bell, like many console EQs.
+/// ConstantQ keeps the q of the poles (for boosts) or zeros (for cuts) at q, so the bandwidth
+/// around the tip of the bell stays the same for all gains.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum PeakShape {
+    #[default]
+    Cookbook,
+    ProportionalQ,
+    ConstantQ,
+}
+
+impl PeakShape {
+    pub const ALL: &'static [PeakShape] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = ["Cookbook", "Proportional Q", "Constant Q"];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+
+    /// q to use with the cookbook peak formulas to get this shape
+    pub fn cookbook_q<F: utils::Float>(&self, gain_db: F, q: F) -> F {
+        let abs_sqrt_gain = utils::db_to_amplitude(F::ONE_HALF * gain_db.abs());
+        match self {
+            PeakShape::Cookbook => q,
+            PeakShape::ProportionalQ => q * abs_sqrt_gain,
+            PeakShape::ConstantQ => q / abs_sqrt_gain,
+        }
+    }
+}
+
+impl TryFrom<usize> for PeakShape {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("PeakShape for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for PeakShape {
+    type Error = &'static str;
+
+    fn try_from(peak_shape_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES
+            .iter()
+            .position(|&name| name == peak_shape_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("PeakShape {} is not defined", value)),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(bound = "F: utils::Float")]
 pub enum Gain<F: utils::Float> {
@@ -115,6 +439,13 @@ impl<F: utils::Float> Gain<F> {
             Gain::Db(db) => *db,
         }
     }
+    pub fn cast<G: utils::Float>(&self) -> Gain<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        match self {
+            Gain::Amplitude(amplitude) => Gain::Amplitude(cast(*amplitude)),
+            Gain::Db(db) => Gain::Db(cast(*db)),
+        }
+    }
 }
 
 // TODO: I bet this can be done better
@@ -155,6 +486,13 @@ impl<F: utils::Float> Frequency<F> {
             Frequency::LogHz(log_hz) => *log_hz,
         }
     }
+    pub fn cast<G: utils::Float>(&self) -> Frequency<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        match self {
+            Frequency::Hz(hz) => Frequency::Hz(cast(*hz)),
+            Frequency::LogHz(log_hz) => Frequency::LogHz(cast(*log_hz)),
+        }
+    }
 }
 
 // TODO: I bet this can be done better
@@ -175,13 +513,237 @@ impl From<Frequency<f64>> for Frequency<f32> {
     }
 }
 
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum BandwidthUnit {
+    #[default]
+    Q,
+    Octaves,
+    ShelfSlope,
+}
+
+impl BandwidthUnit {
+    pub const ALL: &'static [BandwidthUnit] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = ["Q", "Octaves", "Shelf Slope"];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+}
+
+impl TryFrom<usize> for BandwidthUnit {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!(
+                "BandwidthUnit for index {} is not defined",
+                index
+            ))
+        }
+    }
+}
+
+impl TryFrom<&str> for BandwidthUnit {
+    type Error = &'static str;
+
+    fn try_from(unit_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES.iter().position(|&name| name == unit_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("BandwidthUnit {} is not defined", value)),
+        }
+    }
+}
+
+/// Bandwidth of a band, as defined in the cookbook: Octaves is the bandwidth between the
+/// band edges (see biquad::cascade::band_edges), using the analog relation
+/// 1/q = 2*sinh(ln(2)/2 * octaves). ShelfSlope is the shelf slope S, which needs the gain
+/// to be converted: 1/q = sqrt((A + 1/A) * (1/S - 1) + 2) with A = 10^(gain_db/40).
+#[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(bound = "F: utils::Float")]
+pub enum Bandwidth<F: utils::Float> {
+    Q(F),
+    Octaves(F),
+    ShelfSlope(F),
+}
+
+impl<F: utils::Float> Bandwidth<F> {
+    pub fn q(&self, gain_db: F) -> F {
+        match self {
+            Bandwidth::Q(q) => *q,
+            Bandwidth::Octaves(octaves) => {
+                F::ONE / (F::TWO * (F::LN_2() * F::ONE_HALF * *octaves).sinh())
+            }
+            Bandwidth::ShelfSlope(shelf_slope) => {
+                let a_plus_one_through_a = Self::a_plus_one_through_a(gain_db);
+                // slopes steeper than possible for this gain end up with a very high q, they
+                // are kept out where the slope is entered, see shelf_slope_range
+                let one_through_q_squared =
+                    (a_plus_one_through_a * (F::ONE / *shelf_slope - F::ONE) + F::TWO)
+                        .max(F::epsilon());
+                F::ONE / one_through_q_squared.sqrt()
+            }
+        }
+    }
+
+    pub fn octaves(&self, gain_db: F) -> F {
+        match self {
+            Bandwidth::Octaves(octaves) => *octaves,
+            _ => F::TWO / F::LN_2() * (F::ONE_HALF / self.q(gain_db)).asinh(),
+        }
+    }
+
+    pub fn shelf_slope(&self, gain_db: F) -> F {
+        match self {
+            Bandwidth::ShelfSlope(shelf_slope) => *shelf_slope,
+            _ => {
+                let q = self.q(gain_db);
+                let a_plus_one_through_a = Self::a_plus_one_through_a(gain_db);
+                F::ONE / ((F::ONE / (q * q) - F::TWO) / a_plus_one_through_a + F::ONE)
+            }
+        }
+    }
+
+    /// The shelf slopes whose q lies within q_range for the gain, which convert to q and back
+    /// without loss. The steepest possible slope gets flatter with the gain.
+    pub fn shelf_slope_range(q_range: RangeInclusive<F>, gain_db: F) -> RangeInclusive<F> {
+        // the slope grows with q
+        Bandwidth::Q(*q_range.start()).shelf_slope(gain_db)
+            ..=Bandwidth::Q(*q_range.end()).shelf_slope(gain_db)
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Bandwidth<G> {
+        let cast = |value: F| G::from(value).unwrap();
+        match self {
+            Bandwidth::Q(q) => Bandwidth::Q(cast(*q)),
+            Bandwidth::Octaves(octaves) => Bandwidth::Octaves(cast(*octaves)),
+            Bandwidth::ShelfSlope(shelf_slope) => Bandwidth::ShelfSlope(cast(*shelf_slope)),
+        }
+    }
+
+    pub fn unit(&self) -> BandwidthUnit {
+        match self {
+            Bandwidth::Q(_) => BandwidthUnit::Q,
+            Bandwidth::Octaves(_) => BandwidthUnit::Octaves,
+            Bandwidth::ShelfSlope(_) => BandwidthUnit::ShelfSlope,
+        }
+    }
+
+    pub fn to_unit(&self, unit: BandwidthUnit, gain_db: F) -> Self {
+        match unit {
+            BandwidthUnit::Q => Bandwidth::Q(self.q(gain_db)),
+            BandwidthUnit::Octaves => Bandwidth::Octaves(self.octaves(gain_db)),
+            BandwidthUnit::ShelfSlope => Bandwidth::ShelfSlope(self.shelf_slope(gain_db)),
+        }
+    }
+
+    fn a_plus_one_through_a(gain_db: F) -> F {
+        let a = utils::db_to_amplitude(F::ONE_HALF * gain_db);
+        a + F::ONE / a
+    }
+}
+
+// TODO: I bet this can be done better
+impl From<Bandwidth<f32>> for Bandwidth<f64> {
+    fn from(bandwidth: Bandwidth<f32>) -> Self {
+        match bandwidth {
+            Bandwidth::<f32>::Q(q) => Self::Q(q as f64),
+            Bandwidth::<f32>::Octaves(octaves) => Self::Octaves(octaves as f64),
+            Bandwidth::<f32>::ShelfSlope(shelf_slope) => Self::ShelfSlope(shelf_slope as f64),
+        }
+    }
+}
+impl From<Bandwidth<f64>> for Bandwidth<f32> {
+    fn from(bandwidth: Bandwidth<f64>) -> Self {
+        match bandwidth {
+            Bandwidth::<f64>::Q(q) => Self::Q(q as f32),
+            Bandwidth::<f64>::Octaves(octaves) => Self::Octaves(octaves as f32),
+            Bandwidth::<f64>::ShelfSlope(shelf_slope) => Self::ShelfSlope(shelf_slope as f32),
+        }
+    }
+}
+
+/// Eqs stored before Bandwidth existed have a plain "q" number
+fn deserialize_bandwidth<'de, D, F>(deserializer: D) -> Result<Bandwidth<F>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: utils::Float,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged, bound = "F: utils::Float")]
+    enum BandwidthOrQ<F: utils::Float> {
+        Bandwidth(Bandwidth<F>),
+        Q(F),
+    }
+
+    let bandwidth_or_q: BandwidthOrQ<F> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(match bandwidth_or_q {
+        BandwidthOrQ::Bandwidth(bandwidth) => bandwidth,
+        BandwidthOrQ::Q(q) => Bandwidth::Q(q),
+    })
+}
+
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(bound = "F: utils::Float")]
 pub struct Eq<F: utils::Float> {
     pub gain: Gain<F>,
     pub frequency: Frequency<F>,
-    pub q: F,
+    #[serde(alias = "q", deserialize_with = "deserialize_bandwidth")]
+    pub bandwidth: Bandwidth<F>,
     pub eq_type: EqType,
+    #[serde(default)]
+    pub slope: Slope,
+    #[serde(default)]
+    pub alignment: Alignment,
+    #[serde(default)]
+    pub design: Design,
+    #[serde(default)]
+    pub peak_shape: PeakShape,
+}
+
+impl<F: utils::Float> Eq<F> {
+    pub fn q(&self) -> F {
+        self.bandwidth.q(self.gain.db())
+    }
+
+    /// Cookbook band-pass at the frequency of the eq, with its bandwidth as q
+    pub fn to_band_pass(&self) -> Self {
+        Self {
+            gain: Gain::Db(F::ZERO),
+            frequency: self.frequency,
+            bandwidth: Bandwidth::Q(self.q()),
+            eq_type: EqType::BandPass,
+            slope: Slope::default(),
+            alignment: Alignment::default(),
+            design: Design::Cookbook,
+            peak_shape: PeakShape::default(),
+        }
+    }
+
+    pub fn cast<G: utils::Float>(&self) -> Eq<G> {
+        Eq {
+            gain: self.gain.cast(),
+            frequency: self.frequency.cast(),
+            bandwidth: self.bandwidth.cast(),
+            eq_type: self.eq_type,
+            slope: self.slope,
+            alignment: self.alignment,
+            design: self.design,
+            peak_shape: self.peak_shape,
+        }
+    }
 }
 
 // TODO: I bet this can be done better
@@ -190,8 +752,12 @@ impl From<Eq<f32>> for Eq<f64> {
         Self {
             gain: eq.gain.into(),
             frequency: eq.frequency.into(),
-            q: eq.q as f64,
+            bandwidth: eq.bandwidth.into(),
             eq_type: eq.eq_type,
+            slope: eq.slope,
+            alignment: eq.alignment,
+            design: eq.design,
+            peak_shape: eq.peak_shape,
         }
     }
 }
@@ -200,8 +766,12 @@ impl From<Eq<f64>> for Eq<f32> {
         Self {
             gain: eq.gain.into(),
             frequency: eq.frequency.into(),
-            q: eq.q as f32,
+            bandwidth: eq.bandwidth.into(),
             eq_type: eq.eq_type,
+            slope: eq.slope,
+            alignment: eq.alignment,
+            design: eq.design,
+            peak_shape: eq.peak_shape,
         }
     }
 }
@@ -209,6 +779,16 @@ impl From<Eq<f64>> for Eq<f32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn eq_type_indices_are_stable() {
+        // saved plugin states and automation refer to the eq types by index
+        assert_eq!(EqType::HighShelf as usize, 8);
+        assert_eq!(EqType::Bypassed as usize, 9);
+        assert_eq!(EqType::try_from(9).unwrap(), EqType::Bypassed);
+        assert_eq!(EqType::Bypassed.to_string(), "Bypassed");
+    }
 
     #[test]
     fn eq_type_from_and_into_string() {
@@ -233,17 +813,111 @@ mod tests {
         }
     }
 
+    #[test]
+    fn eq_options_from_and_into_string() {
+        for slope in Slope::ALL {
+            assert_eq!(*slope, Slope::try_from(slope.to_string()).unwrap());
+        }
+        for alignment in Alignment::ALL {
+            assert_eq!(
+                *alignment,
+                Alignment::try_from(alignment.to_string()).unwrap()
+            );
+        }
+        for design in Design::ALL {
+            assert_eq!(*design, Design::try_from(design.to_string()).unwrap());
+        }
+        for peak_shape in PeakShape::ALL {
+            assert_eq!(
+                *peak_shape,
+                PeakShape::try_from(peak_shape.to_string()).unwrap()
+            );
+        }
+        for unit in BandwidthUnit::ALL {
+            assert_eq!(*unit, BandwidthUnit::try_from(unit.to_string()).unwrap());
+        }
+    }
+
     #[test]
     fn eq_from_and_into() {
         let eq_f32 = Eq {
             gain: Gain::Db(-3.0_f32),
             frequency: Frequency::Hz(440.0_f32),
-            q: 0.707_f32,
-            eq_type: EqType::Peak,
+            bandwidth: Bandwidth::Octaves(1.5_f32),
+            eq_type: EqType::LowPass,
+            slope: Slope::Db24,
+            alignment: Alignment::LinkwitzRiley,
+            design: Design::AnalogMatched,
+            peak_shape: PeakShape::ProportionalQ,
         };
         let eq_f64: Eq<f64> = Eq::<f64>::from(eq_f32.clone());
 
         let eq_f32_back: Eq<f32> = eq_f64.into();
         assert_eq!(eq_f32, eq_f32_back);
     }
+
+    #[test]
+    fn bandwidth_conversions() {
+        // values from the cookbook: one octave is q = sqrt(2), shelf slope 1 is q = 1/sqrt(2)
+        assert_approx_eq!(Bandwidth::Octaves(1.0_f64).q(0.0), std::f64::consts::SQRT_2);
+        for gain_db in [-12.0, 0.0, 6.0] {
+            assert_approx_eq!(
+                Bandwidth::ShelfSlope(1.0_f64).q(gain_db),
+                std::f64::consts::FRAC_1_SQRT_2
+            );
+        }
+
+        for gain_db in [-15.0, -1.0, 0.0, 4.0, 20.0] {
+            for bandwidth in [
+                Bandwidth::Q(0.3_f64),
+                Bandwidth::Q(4.0),
+                Bandwidth::Octaves(0.25),
+                Bandwidth::Octaves(3.0),
+                Bandwidth::ShelfSlope(0.4),
+                Bandwidth::ShelfSlope(1.0),
+            ] {
+                let q = bandwidth.q(gain_db);
+                for unit in BandwidthUnit::ALL {
+                    let converted = bandwidth.to_unit(*unit, gain_db);
+                    assert_eq!(converted.unit(), *unit);
+                    assert_approx_eq!(converted.q(gain_db), q);
+                    let back = converted.to_unit(bandwidth.unit(), gain_db);
+                    assert_approx_eq!(back.q(gain_db), q);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shelf_slopes_in_range_convert_without_loss() {
+        let q_range = 0.1_f64..=10.0;
+        for gain_db in [-40.0, -12.0, 3.0, 24.0, 40.0] {
+            let range = Bandwidth::shelf_slope_range(q_range.clone(), gain_db);
+            assert!(range.start() < range.end());
+            for step in 0..=10 {
+                let shelf_slope =
+                    range.start() + (range.end() - range.start()) * step as f64 / 10.0;
+                let q = Bandwidth::ShelfSlope(shelf_slope).q(gain_db);
+                assert!(q >= q_range.start() * (1.0 - 1e-9) && q <= q_range.end() * (1.0 + 1e-9));
+                assert_approx_eq!(Bandwidth::Q(q).shelf_slope(gain_db), shelf_slope, 1e-9);
+            }
+        }
+        // at high gains, the slopes of the default slider range get too steep
+        assert!(*Bandwidth::shelf_slope_range(q_range, 40.0).end() < 1.5);
+    }
+
+    #[test]
+    fn eq_with_plain_q_deserializes() {
+        let json = r#"{
+            "gain": {"Db": 3.0},
+            "frequency": {"Hz": 1000.0},
+            "q": 0.7,
+            "eq_type": "Peak"
+        }"#;
+        let eq: Eq<f64> = serde_json::from_str(json).unwrap();
+        assert_eq!(eq.bandwidth, Bandwidth::Q(0.7));
+
+        let eq_back: Eq<f64> = serde_json::from_str(&serde_json::to_string(&eq).unwrap()).unwrap();
+        assert_eq!(eq, eq_back);
+    }
 }
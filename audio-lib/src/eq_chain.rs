@@ -0,0 +1,761 @@
+use crate::biquad::{
+    cascade, coefficients, lattice, simd, svf, tdf2, topology, utils as biquad_utils,
+};
+use crate::{eq, routing, utils};
+use num::Complex;
+use std::ops::Range;
+
+/// Coefficients and per channel states of a band, realised with the section structure S
+struct Realised<S: topology::Section, const NUM_CHANNELS: usize> {
+    coefficients: topology::CascadeCoefficients<S>,
+    states: [topology::CascadeState<S>; NUM_CHANNELS],
+}
+
+impl<S: topology::Section, const NUM_CHANNELS: usize> Realised<S, NUM_CHANNELS> {
+    fn new(design: &cascade::Coefficients<f64>) -> Self {
+        Self {
+            coefficients: topology::CascadeCoefficients::from(design),
+            states: std::array::from_fn(|_| topology::CascadeState::new()),
+        }
+    }
+
+    fn set_coefficients(&mut self, design: &cascade::Coefficients<f64>, reset_states: bool) {
+        self.coefficients = topology::CascadeCoefficients::from(design);
+        if reset_states {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        for state in self.states.iter_mut() {
+            state.reset();
+        }
+    }
+
+    fn process_block(
+        &mut self,
+        channels: &mut [&mut [S::Float]],
+        range: Range<usize>,
+        routed_channels: Range<usize>,
+    ) {
+        for channel in routed_channels {
+            self.states[channel]
+                .process_block(&self.coefficients, &mut channels[channel][range.clone()]);
+        }
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: &mut [simd::Lanes<S::Float, NUM_CHANNELS>],
+        routed_channels: Range<usize>,
+    ) {
+        for frame in frames.iter_mut() {
+            for channel in routed_channels.clone() {
+                frame.0[channel] =
+                    self.states[channel].process(&self.coefficients, frame.0[channel]);
+            }
+        }
+    }
+}
+
+/// Direct form I of all channels at once, with one state of NUM_CHANNELS lanes per section
+/// (see biquad::simd), processing frames of the channels instead of the channels. The
+/// channels that the band doesn't process run through the lanes too, which costs nothing
+/// extra, but their results are dropped.
+struct RealisedLanes<F: utils::Float, const NUM_CHANNELS: usize> {
+    coefficients: cascade::Coefficients<F>,
+    state: simd::CascadeState<F, NUM_CHANNELS>,
+}
+
+impl<F: utils::Float, const NUM_CHANNELS: usize> RealisedLanes<F, NUM_CHANNELS> {
+    fn new(design: &cascade::Coefficients<f64>) -> Self {
+        Self {
+            coefficients: design.cast(),
+            state: simd::CascadeState::new(),
+        }
+    }
+
+    fn set_coefficients(&mut self, design: &cascade::Coefficients<f64>, reset_states: bool) {
+        self.coefficients = design.cast();
+        if reset_states {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: &mut [simd::Lanes<F, NUM_CHANNELS>],
+        routed_channels: Range<usize>,
+    ) {
+        if routed_channels.len() == NUM_CHANNELS {
+            self.state.process_block(&self.coefficients, frames);
+        } else {
+            let mut processed = [simd::Lanes::splat(F::ZERO); LANES_CHUNK_LENGTH];
+            let processed = &mut processed[..frames.len()];
+            processed.copy_from_slice(frames);
+            self.state.process_block(&self.coefficients, processed);
+            for (frame, processed) in frames.iter_mut().zip(processed.iter()) {
+                for channel in routed_channels.clone() {
+                    frame.0[channel] = processed[channel];
+                }
+            }
+        }
+    }
+}
+
+/// A band in the current topology only, the others are realised when switching to them
+enum Realisation<F: utils::Float, const NUM_CHANNELS: usize> {
+    DirectForm1(RealisedLanes<F, NUM_CHANNELS>),
+    StateVariable(Realised<svf::State<F>, NUM_CHANNELS>),
+    TransposedDirectForm2(Realised<tdf2::State<F>, NUM_CHANNELS>),
+    Lattice(Realised<lattice::State<F>, NUM_CHANNELS>),
+}
+
+impl<F: utils::Float, const NUM_CHANNELS: usize> Realisation<F, NUM_CHANNELS> {
+    fn new(topology: topology::Topology, design: &cascade::Coefficients<f64>) -> Self {
+        match topology {
+            topology::Topology::DirectForm1 => Self::DirectForm1(RealisedLanes::new(design)),
+            topology::Topology::StateVariable => Self::StateVariable(Realised::new(design)),
+            topology::Topology::TransposedDirectForm2 => {
+                Self::TransposedDirectForm2(Realised::new(design))
+            }
+            topology::Topology::Lattice => Self::Lattice(Realised::new(design)),
+        }
+    }
+
+    fn set_coefficients(&mut self, design: &cascade::Coefficients<f64>, reset_states: bool) {
+        match self {
+            Self::DirectForm1(realised) => realised.set_coefficients(design, reset_states),
+            Self::StateVariable(realised) => realised.set_coefficients(design, reset_states),
+            Self::TransposedDirectForm2(realised) => {
+                realised.set_coefficients(design, reset_states)
+            }
+            Self::Lattice(realised) => realised.set_coefficients(design, reset_states),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::DirectForm1(realised) => realised.reset(),
+            Self::StateVariable(realised) => realised.reset(),
+            Self::TransposedDirectForm2(realised) => realised.reset(),
+            Self::Lattice(realised) => realised.reset(),
+        }
+    }
+
+    fn process_block(
+        &mut self,
+        channels: &mut [&mut [F]],
+        range: Range<usize>,
+        routed_channels: Range<usize>,
+    ) {
+        match self {
+            Self::DirectForm1(realised) => {
+                let mut frames = [simd::Lanes::splat(F::ZERO); LANES_CHUNK_LENGTH];
+                for chunk in chunks(range) {
+                    let frames = &mut frames[..chunk.len()];
+                    gather_frames(channels, chunk.clone(), frames);
+                    realised.process_frames(frames, routed_channels.clone());
+                    scatter_frames(frames, channels, chunk);
+                }
+            }
+            Self::StateVariable(realised) => {
+                realised.process_block(channels, range, routed_channels)
+            }
+            Self::TransposedDirectForm2(realised) => {
+                realised.process_block(channels, range, routed_channels)
+            }
+            Self::Lattice(realised) => realised.process_block(channels, range, routed_channels),
+        }
+    }
+
+    /// Processes the frames sample by sample in the structures other than the lanes
+    fn process_frames(
+        &mut self,
+        frames: &mut [simd::Lanes<F, NUM_CHANNELS>],
+        routed_channels: Range<usize>,
+    ) {
+        match self {
+            Self::DirectForm1(realised) => realised.process_frames(frames, routed_channels),
+            Self::StateVariable(realised) => realised.process_frames(frames, routed_channels),
+            Self::TransposedDirectForm2(realised) => {
+                realised.process_frames(frames, routed_channels)
+            }
+            Self::Lattice(realised) => realised.process_frames(frames, routed_channels),
+        }
+    }
+}
+
+struct Band<F: utils::Float, const NUM_CHANNELS: usize> {
+    eq: eq::Eq<F>,
+    /// The last valid design of the eq
+    design: cascade::Coefficients<f64>,
+    error: Option<coefficients::Error<f64>>,
+    routing: routing::Routing,
+    bypassed: bool,
+    realisation: Realisation<F, NUM_CHANNELS>,
+}
+
+impl<F: utils::Float, const NUM_CHANNELS: usize> Band<F, NUM_CHANNELS> {
+    fn is_active(&self) -> bool {
+        !self.bypassed && self.eq.eq_type.is_active()
+    }
+}
+
+/// Bands of eqs in series, with their coefficients and the filter states of NUM_CHANNELS
+/// channels, realised in the selected topology (see biquad::topology). Each band filters the
+/// channels of its routing, the first two channels are converted between left/right and
+/// mid/side only where the next band needs the other encoding, and back at the end.
+/// Bands whose eq can't be designed (see Coefficients::try_from_eq) keep their previous
+/// coefficients, or pass the signal through if there are none, and report the error.
+/// Bypassed bands and bands with an inactive eq type are skipped. While a band is soloed, it
+/// runs alone and mutes the channels it doesn't process.
+/// The designs run in f64, since the structures other than the direct forms are precise in
+/// f32 then. Nothing but new and the responses allocates, so the chain can run on the audio
+/// thread.
+pub struct EqChain<F: utils::Float, const NUM_CHANNELS: usize> {
+    sample_rate: F,
+    topology: topology::Topology,
+    solo_band: Option<usize>,
+    bands: Vec<Band<F, NUM_CHANNELS>>,
+}
+
+impl<F: utils::Float, const NUM_CHANNELS: usize> EqChain<F, NUM_CHANNELS> {
+    pub fn new(eqs: impl IntoIterator<Item = eq::Eq<F>>, sample_rate: F) -> Self {
+        let topology = topology::Topology::default();
+        let bands = eqs
+            .into_iter()
+            .map(|eq| {
+                let (design, error) = match Self::design_eq(&eq, sample_rate) {
+                    Ok(design) => (design, None),
+                    Err(error) => (cascade::Coefficients::passthrough(), Some(error)),
+                };
+                let realisation = Realisation::new(topology, &design);
+                Band {
+                    eq,
+                    design,
+                    error,
+                    routing: routing::Routing::default(),
+                    bypassed: false,
+                    realisation,
+                }
+            })
+            .collect();
+        Self {
+            sample_rate,
+            topology,
+            solo_band: None,
+            bands,
+        }
+    }
+
+    pub fn num_bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    pub const fn num_channels(&self) -> usize {
+        NUM_CHANNELS
+    }
+
+    pub fn sample_rate(&self) -> F {
+        self.sample_rate
+    }
+
+    pub fn eq(&self, band: usize) -> &eq::Eq<F> {
+        &self.bands[band].eq
+    }
+
+    pub fn eqs(&self) -> impl Iterator<Item = &eq::Eq<F>> {
+        self.bands.iter().map(|band| &band.eq)
+    }
+
+    /// Redesigns the band if the eq changed. On error, the previous coefficients stay in use
+    /// and the error is kept, see error.
+    pub fn set_eq(&mut self, band: usize, eq: &eq::Eq<F>) {
+        if *eq != self.bands[band].eq {
+            self.bands[band].eq = eq.clone();
+            self.update_coefficients(band);
+        }
+    }
+
+    /// Redesigns all bands and resets the states. Designs for the previous sample rate don't
+    /// fit anymore, so bands that fail pass the signal through. Returns the first of them.
+    pub fn set_sample_rate(
+        &mut self,
+        sample_rate: F,
+    ) -> Result<(), (usize, coefficients::Error<f64>)> {
+        self.sample_rate = sample_rate;
+        for band in self.bands.iter_mut() {
+            band.design = cascade::Coefficients::passthrough();
+            band.realisation.set_coefficients(&band.design, false);
+        }
+        for band in 0..self.bands.len() {
+            self.update_coefficients(band);
+        }
+        self.reset();
+        match self.errors().next() {
+            Some(indexed_error) => Err(indexed_error),
+            None => Ok(()),
+        }
+    }
+
+    pub fn topology(&self) -> topology::Topology {
+        self.topology
+    }
+
+    /// Realises all bands in the topology, from fresh states, since the states of the other
+    /// structure don't carry over
+    pub fn set_topology(&mut self, topology: topology::Topology) {
+        if topology != self.topology {
+            self.topology = topology;
+            for band in self.bands.iter_mut() {
+                band.realisation = Realisation::new(topology, &band.design);
+            }
+        }
+    }
+
+    pub fn routing(&self, band: usize) -> routing::Routing {
+        self.bands[band].routing
+    }
+
+    pub fn set_routing(&mut self, band: usize, routing: routing::Routing) {
+        let band = &mut self.bands[band];
+        if routing != band.routing {
+            band.routing = routing;
+            // the states belong to other channels
+            band.realisation.reset();
+        }
+    }
+
+    pub fn set_bypassed(&mut self, band: usize, bypassed: bool) {
+        let band = &mut self.bands[band];
+        if bypassed != band.bypassed {
+            band.bypassed = bypassed;
+            // the states of a band that was skipped are stale
+            band.realisation.reset();
+        }
+    }
+
+    pub fn is_bypassed(&self, band: usize) -> bool {
+        self.bands[band].bypassed
+    }
+
+    /// Whether the band takes part in the processing
+    pub fn is_active(&self, band: usize) -> bool {
+        self.bands[band].is_active()
+    }
+
+    pub fn solo_band(&self) -> Option<usize> {
+        self.solo_band
+    }
+
+    pub fn set_solo_band(&mut self, solo_band: Option<usize>) {
+        if solo_band != self.solo_band {
+            // the states belong to the other chain
+            self.reset();
+            self.solo_band = solo_band;
+        }
+    }
+
+    pub fn error(&self, band: usize) -> Option<coefficients::Error<f64>> {
+        self.bands[band].error
+    }
+
+    /// Band indices with their errors
+    pub fn errors(&self) -> impl Iterator<Item = (usize, coefficients::Error<f64>)> {
+        self.bands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, band)| band.error.map(|error| (index, error)))
+    }
+
+    /// The design in use for the band
+    pub fn design(&self, band: usize) -> &cascade::Coefficients<f64> {
+        &self.bands[band].design
+    }
+
+    pub fn coefficients(&self, band: usize) -> cascade::Coefficients<F> {
+        self.bands[band].design.cast()
+    }
+
+    /// The coefficients of the band, if it is active
+    pub fn active_coefficients(&self, band: usize) -> Option<cascade::Coefficients<F>> {
+        self.is_active(band).then(|| self.coefficients(band))
+    }
+
+    pub fn reset(&mut self) {
+        for band in self.bands.iter_mut() {
+            band.realisation.reset();
+        }
+    }
+
+    /// Runs the samples in range of the channels through the bands. In the direct form I
+    /// topology, chunks of the channels are interleaved into frames once, so that all bands
+    /// process all channels at once (see biquad::simd), the other structures process the
+    /// channels one by one.
+    pub fn process_block(&mut self, channels: &mut [&mut [F]], range: Range<usize>) {
+        assert!(channels.len() <= NUM_CHANNELS);
+        let num_channels = channels.len();
+        if self.topology != topology::Topology::DirectForm1 {
+            self.process_bands(num_channels, |step| match step {
+                Step::ConvertEncoding(to) => convert_encoding(channels, range.clone(), to),
+                Step::Mute(channel) => channels[channel][range.clone()].fill(F::ZERO),
+                Step::Process(realisation, routed_channels) => {
+                    realisation.process_block(channels, range.clone(), routed_channels)
+                }
+            });
+            return;
+        }
+        let mut frames = [simd::Lanes::splat(F::ZERO); LANES_CHUNK_LENGTH];
+        for chunk in chunks(range) {
+            let frames = &mut frames[..chunk.len()];
+            gather_frames(channels, chunk.clone(), frames);
+            self.process_bands(num_channels, |step| match step {
+                Step::ConvertEncoding(to) => {
+                    if num_channels > 1 {
+                        for frame in frames.iter_mut() {
+                            (frame.0[0], frame.0[1]) = routing::convert(frame[0], frame[1], to);
+                        }
+                    }
+                }
+                Step::Mute(channel) => {
+                    for frame in frames.iter_mut() {
+                        frame.0[channel] = F::ZERO;
+                    }
+                }
+                Step::Process(realisation, routed_channels) => {
+                    realisation.process_frames(frames, routed_channels)
+                }
+            });
+            scatter_frames(frames, channels, chunk);
+        }
+    }
+
+    /// Response of all active bands together
+    pub fn frequency_response(&self, frequency: F) -> Complex<F> {
+        let active_coefficients =
+            (0..self.num_bands()).filter_map(|band| self.active_coefficients(band));
+        biquad_utils::multiband::make_frequency_response(active_coefficients, self.sample_rate)(
+            frequency,
+        )
+    }
+
+    /// Impulse response of all active bands together, with fresh states (see
+    /// biquad::utils::impulse_response)
+    pub fn impulse_response(&self, eps: F, hold_length: usize, max_length: usize) -> Vec<F> {
+        let active_coefficients =
+            (0..self.num_bands()).filter_map(|band| self.active_coefficients(band));
+        biquad_utils::multiband::impulse_response_for_coefficients(
+            active_coefficients,
+            eps,
+            hold_length,
+            max_length,
+        )
+    }
+
+    /// Bands run in order, the first two channels are converted between left/right and
+    /// mid/side only where the next band needs the other encoding, and back at the end.
+    /// A soloed band runs alone, and mutes the channels it doesn't process.
+    fn process_bands(
+        &mut self,
+        num_channels: usize,
+        mut step: impl FnMut(Step<'_, F, NUM_CHANNELS>),
+    ) {
+        let solo_band = self.solo_band;
+        let mut encoding = routing::Encoding::LeftRight;
+        for (index, band) in self.bands.iter_mut().enumerate() {
+            if solo_band.is_some_and(|solo_band| solo_band != index) {
+                continue;
+            }
+            if let Some(band_encoding) = band.routing.encoding()
+                && band_encoding != encoding
+            {
+                step(Step::ConvertEncoding(band_encoding));
+                encoding = band_encoding;
+            }
+            let routed_channels = band.routing.channels(num_channels);
+            if solo_band == Some(index) {
+                for channel in 0..num_channels {
+                    if !routed_channels.contains(&channel) {
+                        step(Step::Mute(channel));
+                    }
+                }
+            }
+            if band.is_active() {
+                step(Step::Process(&mut band.realisation, routed_channels));
+            }
+        }
+        if encoding != routing::Encoding::LeftRight {
+            step(Step::ConvertEncoding(routing::Encoding::LeftRight));
+        }
+    }
+
+    fn design_eq(
+        eq: &eq::Eq<F>,
+        sample_rate: F,
+    ) -> Result<cascade::Coefficients<f64>, coefficients::Error<f64>> {
+        cascade::Coefficients::try_from_eq(&eq.cast(), sample_rate.to_f64().unwrap())
+    }
+
+    fn update_coefficients(&mut self, band: usize) {
+        let band = &mut self.bands[band];
+        match Self::design_eq(&band.eq, self.sample_rate) {
+            Ok(design) => {
+                // sections that weren't in use before carry stale states
+                let reset_states = design.sections().len() != band.design.sections().len();
+                band.design = design;
+                band.realisation
+                    .set_coefficients(&band.design, reset_states);
+                band.error = None;
+            }
+            Err(error) => band.error = Some(error),
+        }
+    }
+}
+
+/// What process_bands asks for from the signal, in the channels or in frames
+enum Step<'a, F: utils::Float, const NUM_CHANNELS: usize> {
+    ConvertEncoding(routing::Encoding),
+    Mute(usize),
+    Process(&'a mut Realisation<F, NUM_CHANNELS>, Range<usize>),
+}
+
+/// Frames interleaved at once on the stack, so that processing doesn't allocate
+const LANES_CHUNK_LENGTH: usize = 64;
+
+fn chunks(range: Range<usize>) -> impl Iterator<Item = Range<usize>> {
+    range
+        .clone()
+        .step_by(LANES_CHUNK_LENGTH)
+        .map(move |start| start..(start + LANES_CHUNK_LENGTH).min(range.end))
+}
+
+/// Missing channels are silent lanes
+fn gather_frames<F: utils::Float, const NUM_CHANNELS: usize>(
+    channels: &[&mut [F]],
+    chunk: Range<usize>,
+    frames: &mut [simd::Lanes<F, NUM_CHANNELS>],
+) {
+    for (frame, index) in frames.iter_mut().zip(chunk) {
+        *frame = simd::Lanes(std::array::from_fn(|channel| {
+            channels
+                .get(channel)
+                .map_or(F::ZERO, |samples| samples[index])
+        }));
+    }
+}
+
+fn scatter_frames<F: utils::Float, const NUM_CHANNELS: usize>(
+    frames: &[simd::Lanes<F, NUM_CHANNELS>],
+    channels: &mut [&mut [F]],
+    chunk: Range<usize>,
+) {
+    for (channel, samples) in channels.iter_mut().enumerate() {
+        for (sample, frame) in samples[chunk.clone()].iter_mut().zip(frames) {
+            *sample = frame[channel];
+        }
+    }
+}
+
+fn convert_encoding<F: utils::Float>(
+    channels: &mut [&mut [F]],
+    range: Range<usize>,
+    to: routing::Encoding,
+) {
+    if let [left_or_mid, right_or_side, ..] = channels {
+        routing::convert_block(
+            &mut left_or_mid[range.clone()],
+            &mut right_or_side[range],
+            to,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn make_eq(eq_type: eq::EqType, frequency: f64, gain_db: f64) -> eq::Eq<f64> {
+        eq::Eq {
+            gain: eq::Gain::Db(gain_db),
+            frequency: eq::Frequency::Hz(frequency),
+            bandwidth: eq::Bandwidth::Q(0.8),
+            eq_type,
+            slope: eq::Slope::Db24,
+            alignment: eq::Alignment::Butterworth,
+            design: eq::Design::Cookbook,
+            peak_shape: eq::PeakShape::default(),
+        }
+    }
+
+    fn make_chain() -> EqChain<f64, 2> {
+        EqChain::new(
+            [
+                make_eq(eq::EqType::HighPass, 40.0, 0.0),
+                make_eq(eq::EqType::Peak, 1000.0, 6.0),
+                make_eq(eq::EqType::HighShelf, 8000.0, -4.0),
+            ],
+            48000.0,
+        )
+    }
+
+    /// Runs an impulse on the given channel through the chain, returns both channels
+    fn process_impulse(
+        chain: &mut EqChain<f64, 2>,
+        channel: usize,
+        length: usize,
+    ) -> [Vec<f64>; 2] {
+        let mut channels = [vec![0.0; length], vec![0.0; length]];
+        channels[channel][0] = 1.0;
+        let [left, right] = &mut channels;
+        chain.process_block(&mut [left.as_mut_slice(), right.as_mut_slice()], 0..length);
+        channels
+    }
+
+    #[test]
+    fn processing_matches_the_responses() {
+        let mut chain = make_chain();
+        let impulse_response = chain.impulse_response(1e-12, 10, 48000);
+        let [silence, impulse] = process_impulse(&mut chain, 1, impulse_response.len());
+        for (actual, expected) in impulse.iter().zip(impulse_response.iter()) {
+            assert_approx_eq!(actual, expected, 1e-12);
+        }
+        // the other channel is untouched
+        assert!(silence.iter().all(|sample| *sample == 0.0));
+
+        let expected_response = biquad_utils::multiband::make_frequency_response(
+            (0..3).map(|band| chain.coefficients(band)),
+            48000.0,
+        );
+        for frequency in [20.0, 1000.0, 10000.0] {
+            let response = chain.frequency_response(frequency);
+            assert_approx_eq!(response.re, expected_response(frequency).re);
+            assert_approx_eq!(response.im, expected_response(frequency).im);
+        }
+    }
+
+    #[test]
+    fn all_topologies_process_alike() {
+        let mut chain = make_chain();
+        let expected = process_impulse(&mut chain, 0, 2000);
+        for topology in topology::Topology::ALL {
+            chain.set_topology(*topology);
+            chain.reset();
+            let actual = process_impulse(&mut chain, 0, 2000);
+            for (actual, expected) in actual[0].iter().zip(expected[0].iter()) {
+                assert_approx_eq!(actual, expected, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn bands_process_the_channels_of_their_routing() {
+        let mut chain = make_chain();
+        let stereo = process_impulse(&mut chain, 0, 1000);
+        chain.reset();
+        for band in 0..3 {
+            chain.set_routing(band, routing::Routing::Right);
+        }
+        // the left channel passes, the right one is filtered like all channels before
+        let [left, right] = process_impulse(&mut chain, 0, 1000);
+        assert_eq!(left[0], 1.0);
+        assert!(left[1..].iter().all(|sample| *sample == 0.0));
+        assert!(right.iter().all(|sample| *sample == 0.0));
+        let [_, right] = process_impulse(&mut chain, 1, 1000);
+        assert_eq!(right, stereo[0]);
+
+        // a mid band filters both channels alike for a signal in both of them, and leaves
+        // the difference of the channels alone
+        chain.reset();
+        for band in 0..3 {
+            chain.set_routing(band, routing::Routing::Mid);
+        }
+        let mut channels = [vec![0.0; 1000], vec![0.0; 1000]];
+        channels[0][0] = 1.0;
+        channels[1][0] = 1.0;
+        let [left, right] = &mut channels;
+        chain.process_block(&mut [left.as_mut_slice(), right.as_mut_slice()], 0..1000);
+        for ((left, right), expected) in channels[0]
+            .iter()
+            .zip(channels[1].iter())
+            .zip(stereo[0].iter())
+        {
+            assert_approx_eq!(left, expected, 1e-12);
+            assert_approx_eq!(right, expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn soloed_band_runs_alone_and_mutes_the_other_channels() {
+        let mut chain = make_chain();
+        chain.set_routing(1, routing::Routing::Left);
+        chain.set_solo_band(Some(1));
+        let [left, _] = process_impulse(&mut chain, 0, 1000);
+        let [_, right] = process_impulse(&mut chain, 1, 1000);
+        assert!(right.iter().all(|sample| *sample == 0.0));
+
+        let mut peak = EqChain::<f64, 2>::new([chain.eq(1).clone()], 48000.0);
+        let [expected, _] = process_impulse(&mut peak, 0, 1000);
+        for (actual, expected) in left.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn bypassed_and_inactive_bands_are_skipped() {
+        let mut chain = make_chain();
+        chain.set_bypassed(1, true);
+        let mut bypassed_eq = chain.eq(2).clone();
+        bypassed_eq.eq_type = eq::EqType::Bypassed;
+        chain.set_eq(2, &bypassed_eq);
+        assert_eq!(chain.error(2), None);
+        assert!(chain.is_active(0));
+        assert!(!chain.is_active(1));
+        assert!(!chain.is_active(2));
+
+        let high_pass =
+            biquad_utils::cascade::make_frequency_response(chain.coefficients(0), 48000.0);
+        let response = chain.frequency_response(1000.0);
+        assert_approx_eq!(response.re, high_pass(1000.0).re);
+        assert_approx_eq!(response.im, high_pass(1000.0).im);
+    }
+
+    #[test]
+    fn errors_keep_the_previous_coefficients() {
+        let mut chain = make_chain();
+        let previous_b0 = chain.coefficients(1).sections()[0].b0;
+        let invalid_eq = make_eq(eq::EqType::Peak, 30000.0, 6.0);
+        chain.set_eq(1, &invalid_eq);
+        assert!(matches!(
+            chain.error(1),
+            Some(coefficients::Error::FrequencyAboveNyquist { .. })
+        ));
+        assert_eq!(chain.coefficients(1).sections()[0].b0, previous_b0);
+        assert_eq!(
+            chain.errors().map(|(band, _)| band).collect::<Vec<_>>(),
+            [1]
+        );
+
+        // with a higher sample rate, the frequency is valid
+        assert!(chain.set_sample_rate(96000.0).is_ok());
+        assert_eq!(chain.error(1), None);
+        assert_ne!(chain.coefficients(1).sections()[0].b0, previous_b0);
+        // and lower again, the high shelf is invalid as well, both pass the signal through
+        assert_eq!(
+            chain.set_sample_rate(12000.0).map_err(|(band, _)| band),
+            Err(1)
+        );
+        assert_eq!(chain.errors().count(), 2);
+        for band in 1..3 {
+            let passthrough = chain.coefficients(band);
+            assert_eq!(passthrough.sections().len(), 1);
+            assert_eq!(passthrough.sections()[0].b0, 1.0);
+            assert_eq!(passthrough.sections()[0].a1, 0.0);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::biquad::{cascade, utils as biquad_utils};
+use crate::{utils, windows};
+
+/// FIR design by frequency sampling of a magnitude response. The response is sampled much finer
+/// than the number of taps, so the time aliasing of responses longer than the FIR is small, and
+/// the truncation to the FIR length is smoothed by a window.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Phase {
+    /// Symmetric taps with a constant delay of (length - 1) / 2 samples, no phase distortion
+    Linear,
+    /// Smallest delay for the magnitude, the same phase as minimum phase IIR designs
+    Minimum,
+}
+
+/// Number of frequency samples per tap
+const OVERSAMPLING: usize = 8;
+
+/// Magnitudes are limited to this (about -200 dB) for the logarithm of minimum phase designs
+const MIN_MAGNITUDE: f64 = 1e-10;
+
+/// FIR taps with the magnitude response of the bands in series, which are given like in
+/// biquad::utils::multiband
+pub fn from_coefficients<
+    F: utils::Float + rustfft::FftNum,
+    B: Into<cascade::Coefficients<F>>,
+    C: IntoIterator<Item = B>,
+>(
+    coefficients: C,
+    sample_rate: F,
+    length: usize,
+    phase: Phase,
+    window_type: windows::WindowType,
+) -> Vec<F> {
+    let frequency_response =
+        biquad_utils::multiband::make_frequency_response(coefficients, sample_rate);
+    design(
+        |frequency| frequency_response(frequency).norm(),
+        sample_rate,
+        length,
+        phase,
+        window_type,
+    )
+}
+
+/// FIR taps with the given magnitude response, a function of the frequency in Hz
+pub fn design<F: utils::Float + rustfft::FftNum>(
+    magnitude_response: impl Fn(F) -> F,
+    sample_rate: F,
+    length: usize,
+    phase: Phase,
+    window_type: windows::WindowType,
+) -> Vec<F> {
+    assert!(length > 1);
+    let fft_length = (OVERSAMPLING * length).next_power_of_two();
+    let frequency_step = crate::fft::frequency_step(fft_length, sample_rate);
+    let magnitudes = (0..=fft_length / 2)
+        .map(|bin| magnitude_response(F::from(bin).unwrap() * frequency_step))
+        .collect::<Vec<_>>();
+    match phase {
+        Phase::Linear => linear_phase(&magnitudes, fft_length, length, window_type),
+        Phase::Minimum => minimum_phase(&magnitudes, fft_length, length, window_type),
+    }
+}
+
+/// Delay of linear phase FIRs in samples
+pub fn linear_phase_delay<F: utils::Float>(length: usize) -> F {
+    F::from(length - 1).unwrap() * F::ONE_HALF
+}
+
+fn linear_phase<F: utils::Float + rustfft::FftNum>(
+    magnitudes: &[F],
+    fft_length: usize,
+    length: usize,
+    window_type: windows::WindowType,
+) -> Vec<F> {
+    let delay = linear_phase_delay::<F>(length);
+    let nyquist_bin = fft_length / 2;
+    let mut spectrum = (0..fft_length)
+        .map(|bin| {
+            // signed bins, so the spectrum stays conjugate symmetric for half sample delays
+            let (magnitude, signed_bin) = if bin <= nyquist_bin {
+                (magnitudes[bin], F::from(bin).unwrap())
+            } else {
+                let mirrored_bin = fft_length - bin;
+                (magnitudes[mirrored_bin], -F::from(mirrored_bin).unwrap())
+            };
+            let omega = F::TWO_PI * signed_bin / F::from(fft_length).unwrap();
+            num::Complex::from_polar(magnitude, -omega * delay)
+        })
+        .collect::<Vec<_>>();
+    if length.is_multiple_of(2) {
+        // even lengths have a zero at Nyquist, and the phase there couldn't be real anyway
+        spectrum[nyquist_bin] = num::Complex::from(F::ZERO);
+    }
+    inverse_fft(&mut spectrum);
+
+    let window = windows::make_window::<F>(length + 2, window_type);
+    spectrum
+        .iter()
+        .zip(window.iter().skip(1))
+        .take(length)
+        .map(|(sample, window_value)| sample.re * *window_value)
+        .collect()
+}
+
+/// Homomorphic design: the minimum phase is the Hilbert transform of the log magnitude, which
+/// is computed by folding the real cepstrum onto the positive times.
+fn minimum_phase<F: utils::Float + rustfft::FftNum>(
+    magnitudes: &[F],
+    fft_length: usize,
+    length: usize,
+    window_type: windows::WindowType,
+) -> Vec<F> {
+    let min_magnitude = F::from(MIN_MAGNITUDE).unwrap();
+    let nyquist_bin = fft_length / 2;
+    let mut cepstrum = (0..fft_length)
+        .map(|bin| {
+            let magnitude = magnitudes[bin.min(fft_length - bin)];
+            num::Complex::from(magnitude.max(min_magnitude).ln())
+        })
+        .collect::<Vec<_>>();
+    inverse_fft(&mut cepstrum);
+
+    let two = num::Complex::from(F::TWO);
+    for (time, value) in cepstrum.iter_mut().enumerate() {
+        if time > nyquist_bin {
+            *value = num::Complex::from(F::ZERO);
+        } else if time > 0 && time < nyquist_bin {
+            *value = *value * two;
+        }
+    }
+    forward_fft(&mut cepstrum);
+    let mut spectrum = cepstrum;
+    for value in spectrum.iter_mut() {
+        *value = value.exp();
+    }
+    inverse_fft(&mut spectrum);
+
+    // only the decaying half of the window, the start of the response stays untouched
+    let window = windows::make_window::<F>(2 * length + 1, window_type);
+    spectrum
+        .iter()
+        .zip(window.iter().skip(length))
+        .take(length)
+        .map(|(sample, window_value)| sample.re * *window_value)
+        .collect()
+}
+
+fn forward_fft<F: utils::Float + rustfft::FftNum>(buffer: &mut [num::Complex<F>]) {
+    let mut planner = rustfft::FftPlanner::<F>::new();
+    planner.plan_fft_forward(buffer.len()).process(buffer);
+}
+
+/// Normalised, unlike rustfft
+fn inverse_fft<F: utils::Float + rustfft::FftNum>(buffer: &mut [num::Complex<F>]) {
+    let mut planner = rustfft::FftPlanner::<F>::new();
+    planner.plan_fft_inverse(buffer.len()).process(buffer);
+    let scale = F::ONE / F::from(buffer.len()).unwrap();
+    for value in buffer.iter_mut() {
+        *value = *value * scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eq;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn make_bands(sample_rate: f64) -> Vec<cascade::Coefficients<f64>> {
+        [
+            (eq::EqType::Peak, 200.0, 6.0),
+            (eq::EqType::HighShelf, 6000.0, -8.0),
+            (eq::EqType::Notch, 1000.0, 0.0),
+        ]
+        .iter()
+        .map(|(eq_type, frequency, gain_db)| {
+            cascade::Coefficients::from_eq(
+                &eq::Eq {
+                    gain: eq::Gain::Db(*gain_db),
+                    frequency: eq::Frequency::Hz(*frequency),
+                    bandwidth: eq::Bandwidth::Q(2.0),
+                    eq_type: *eq_type,
+                    slope: eq::Slope::Db12,
+                    alignment: eq::Alignment::Butterworth,
+                    design: eq::Design::Cookbook,
+                    peak_shape: eq::PeakShape::default(),
+                },
+                sample_rate,
+            )
+        })
+        .collect()
+    }
+
+    fn fir_frequency_response(taps: &[f64], frequency: f64, sample_rate: f64) -> num::Complex<f64> {
+        let omega = utils::omega(frequency, sample_rate);
+        taps.iter()
+            .enumerate()
+            .map(|(n, tap)| num::Complex::from_polar(*tap, -omega * n as f64))
+            .sum()
+    }
+
+    #[test]
+    fn linear_phase_matches_the_magnitude() {
+        let sample_rate = 48000.0;
+        let bands = make_bands(sample_rate);
+        let frequency_response =
+            biquad_utils::multiband::make_frequency_response(bands.clone(), sample_rate);
+        for length in [4095, 4096] {
+            let taps = from_coefficients(
+                bands.clone(),
+                sample_rate,
+                length,
+                Phase::Linear,
+                windows::WindowType::VonHann,
+            );
+            assert_eq!(taps.len(), length);
+            for n in 0..length / 2 {
+                assert_approx_eq!(taps[n], taps[length - 1 - n], 1e-12);
+            }
+            // the window smooths the magnitude a little, most at the peak
+            for frequency in [100.0, 200.0, 3000.0, 6000.0, 15000.0] {
+                let expected_db = utils::amplitude_to_db(frequency_response(frequency).norm());
+                let response = fir_frequency_response(&taps, frequency, sample_rate);
+                assert_approx_eq!(utils::amplitude_to_db(response.norm()), expected_db, 0.25);
+            }
+        }
+    }
+
+    #[test]
+    fn minimum_phase_matches_the_minimum_phase_iir() {
+        // peaks, shelves and notches of the cookbook have their zeros on or within the unit
+        // circle, so the impulse response of the chain is minimum phase as well
+        let sample_rate = 48000.0;
+        let bands = make_bands(sample_rate);
+        let length = 4096;
+        let taps = from_coefficients(
+            bands.clone(),
+            sample_rate,
+            length,
+            Phase::Minimum,
+            windows::WindowType::VonHann,
+        );
+        let impulse_response =
+            biquad_utils::multiband::impulse_response_for_coefficients(bands, 0.0, 0, length);
+        for (tap, expected) in taps.iter().zip(impulse_response.iter()).take(256) {
+            assert_approx_eq!(tap, expected, 1e-3);
+        }
+    }
+}
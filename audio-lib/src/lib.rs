@@ -1,9 +1,16 @@
 #![allow(dead_code)]
 
 pub mod biquad;
+pub mod convolution;
+pub mod dynamic_eq;
 pub mod envelope_follower;
 pub mod eq;
+pub mod eq_chain;
 pub mod fft;
+pub mod fir;
+pub mod routing;
 pub mod spsc;
 pub mod utils;
 pub mod windows;
+
+pub use eq_chain::EqChain;
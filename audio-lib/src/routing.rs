@@ -0,0 +1,143 @@
+use crate::utils;
+use enum_table::Enumable;
+use std::ops::Range;
+
+/// Which channels of a stereo signal a band processes. Mid and Side work on the mid/side
+/// encoding of the channels (see encode_mid_side), Left and Right on the channels themselves.
+/// Stereo filters all channels alike, which is the same in both encodings. With a single
+/// channel, Left and Mid process it, Right and Side leave it untouched.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    enum_table::Enumable,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Routing {
+    #[default]
+    Stereo,
+    Left,
+    Right,
+    Mid,
+    Side,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Encoding {
+    LeftRight,
+    MidSide,
+}
+
+impl Routing {
+    pub const ALL: &'static [Routing] = Enumable::VARIANTS;
+    pub const VARIANT_COUNT: usize = Self::COUNT;
+
+    pub const ALL_NAMES: [&'static str; Self::COUNT] = ["Stereo", "Left", "Right", "Mid", "Side"];
+    pub fn to_string(&self) -> &str {
+        Self::ALL_NAMES[*self as usize]
+    }
+
+    /// The encoding the band needs, None for Stereo which works in both
+    pub const fn encoding(&self) -> Option<Encoding> {
+        match self {
+            Routing::Stereo => None,
+            Routing::Left | Routing::Right => Some(Encoding::LeftRight),
+            Routing::Mid | Routing::Side => Some(Encoding::MidSide),
+        }
+    }
+
+    /// The channels of the encoding that the band processes
+    pub fn channels(&self, num_channels: usize) -> Range<usize> {
+        match self {
+            Routing::Stereo => 0..num_channels,
+            Routing::Left | Routing::Mid => 0..num_channels.min(1),
+            Routing::Right | Routing::Side => num_channels.min(1)..num_channels.min(2),
+        }
+    }
+}
+
+impl TryFrom<usize> for Routing {
+    type Error = &'static str;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index < Self::COUNT {
+            Ok(Self::ALL[index])
+        } else {
+            Err(stringify!("Routing for index {} is not defined", index))
+        }
+    }
+}
+
+impl TryFrom<&str> for Routing {
+    type Error = &'static str;
+
+    fn try_from(routing_name: &str) -> Result<Self, Self::Error> {
+        let index_option = Self::ALL_NAMES
+            .iter()
+            .position(|&name| name == routing_name);
+        match index_option {
+            Some(index) => Ok(Self::ALL[index]),
+            None => Err(stringify!("Routing {} is not defined", value)),
+        }
+    }
+}
+
+/// Mid and side with unity gain for signals in both channels, decode_mid_side inverts it
+pub fn encode_mid_side<F: utils::Float>(left: F, right: F) -> (F, F) {
+    ((left + right) * F::ONE_HALF, (left - right) * F::ONE_HALF)
+}
+
+pub fn decode_mid_side<F: utils::Float>(mid: F, side: F) -> (F, F) {
+    (mid + side, mid - side)
+}
+
+/// Converts a pair of samples to the encoding
+pub fn convert<F: utils::Float>(left_or_mid: F, right_or_side: F, to: Encoding) -> (F, F) {
+    match to {
+        Encoding::MidSide => encode_mid_side(left_or_mid, right_or_side),
+        Encoding::LeftRight => decode_mid_side(left_or_mid, right_or_side),
+    }
+}
+
+/// Converts a pair of channels in place between the encodings
+pub fn convert_block<F: utils::Float>(
+    left_or_mid: &mut [F],
+    right_or_side: &mut [F],
+    to: Encoding,
+) {
+    for (first, second) in left_or_mid.iter_mut().zip(right_or_side.iter_mut()) {
+        (*first, *second) = convert(*first, *second, to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mid_side_round_trip() {
+        let left = vec![1.0, 0.5, -0.25, 0.0];
+        let right = vec![1.0, -0.5, 0.75, 0.125];
+        let (mut first, mut second) = (left.clone(), right.clone());
+        convert_block(&mut first, &mut second, Encoding::MidSide);
+        // identical channels are all mid
+        assert_eq!((first[0], second[0]), (1.0, 0.0));
+        assert_eq!((first[1], second[1]), (0.0, 0.5));
+        convert_block(&mut first, &mut second, Encoding::LeftRight);
+        assert_eq!((first, second), (left, right));
+    }
+
+    #[test]
+    fn channels_of_the_routings() {
+        assert_eq!(Routing::Stereo.channels(2), 0..2);
+        assert_eq!(Routing::Mid.channels(2), 0..1);
+        assert_eq!(Routing::Side.channels(2), 1..2);
+        assert_eq!(Routing::Left.channels(1), 0..1);
+        assert!(Routing::Right.channels(1).is_empty());
+        assert_eq!(Routing::Stereo.encoding(), None);
+        assert_eq!(Routing::Side.encoding(), Some(Encoding::MidSide));
+    }
+}
@@ -98,6 +98,50 @@ pub fn make_phase_response<F: Float>(
     move |frequency| complex_frequency_response(frequency).arg()
 }
 
+/// Phase delay -phase / omega in seconds from an unwrapped phase response in radians, which is
+/// not defined at frequency 0
+pub fn make_phase_delay_response<F: Float>(
+    unwrapped_phase_response: impl Fn(F) -> F,
+) -> impl Fn(F) -> F {
+    move |frequency| -unwrapped_phase_response(frequency) / (F::TWO_PI * frequency)
+}
+
+/// Removes the jumps of 2 pi between consecutive phases in radians, for sampled phases like those
+/// of measured or FFT based responses. The phase must not change by more than pi between samples.
+pub fn unwrap_phase<F: Float>(phases: &mut [F]) {
+    let mut offset = F::ZERO;
+    let mut previous = match phases.first() {
+        Some(first) => *first,
+        None => return,
+    };
+    for phase in phases.iter_mut().skip(1) {
+        let wrapped = *phase;
+        offset = offset - F::TWO_PI * ((wrapped - previous) / F::TWO_PI).round();
+        previous = wrapped;
+        *phase = wrapped + offset;
+    }
+}
+
+/// Energy of the signal up to each sample relative to its total energy, rising from 0 to 1. For
+/// impulse responses, the time to reach e.g. 0.99 shows how long a filter rings, and one minus
+/// it is the (Schroeder) energy decay curve.
+pub fn cumulative_energy<F: Float>(signal: &[F]) -> Vec<F> {
+    let mut energy = F::ZERO;
+    let mut cumulative_energy = signal
+        .iter()
+        .map(|sample| {
+            energy += *sample * *sample;
+            energy
+        })
+        .collect::<Vec<_>>();
+    if energy > F::ZERO {
+        for value in cumulative_energy.iter_mut() {
+            *value = *value / energy;
+        }
+    }
+    cumulative_energy
+}
+
 /// complex roots of polynom c2*x^2 + c1*x + c0
 pub fn polynom_roots<F: Float>(c2: F, c1: F, c0: F) -> PolynomRoots<F> {
     if c2 == F::ZERO {
@@ -187,4 +231,28 @@ mod tests {
         check_solutions(1.2, 2.3, 3.4);
         check_solutions(-17.2, 23.5, 0.004);
     }
+
+    #[test]
+    fn unwrap_sampled_phase() {
+        let slope = -0.9;
+        let expected = (0..50).map(|n| n as f64 * slope).collect::<Vec<_>>();
+        let mut phases = expected
+            .iter()
+            .map(|phase| num::Complex::from_polar(1.0, *phase).arg())
+            .collect::<Vec<_>>();
+        unwrap_phase(&mut phases);
+        for (phase, expected_phase) in phases.iter().zip(expected.iter()) {
+            assert_approx_eq!(phase, expected_phase);
+        }
+
+        let delay = make_phase_delay_response(|frequency: f64| -1e-3 * frequency);
+        assert_approx_eq!(delay(440.0), 1e-3 / std::f64::consts::TAU);
+    }
+
+    #[test]
+    fn cumulative_energy_of_signals() {
+        let cumulative = cumulative_energy(&[2.0, 0.0, -1.0, 1.0]);
+        assert_eq!(cumulative, vec![4.0 / 6.0, 4.0 / 6.0, 5.0 / 6.0, 1.0]);
+        assert_eq!(cumulative_energy(&[0.0_f32; 3]), vec![0.0; 3]);
+    }
 }
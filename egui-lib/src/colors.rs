@@ -4,6 +4,9 @@ pub struct ColorPalette {
     pub eq_stroke: [egui::Color32; 8],
     pub multiband_stroke: egui::Color32,
     pub spectrum_fill: egui::Color32,
+    pub sidechain_spectrum_fill: egui::Color32,
+    /// Of the multiband curves of the routing targets other than stereo, see routing::Routing
+    pub routing_stroke: [egui::Color32; 4],
 }
 
 impl Default for ColorPalette {
@@ -22,6 +25,13 @@ impl Default for ColorPalette {
             ],
             multiband_stroke: egui::Color32::from_rgba_unmultiplied(220, 220, 220, 150),
             spectrum_fill: egui::Color32::from_rgba_unmultiplied(160, 175, 190, 50),
+            sidechain_spectrum_fill: egui::Color32::from_rgba_unmultiplied(190, 150, 90, 50),
+            routing_stroke: [
+                egui::Color32::from_rgba_unmultiplied(120, 190, 230, 150),
+                egui::Color32::from_rgba_unmultiplied(230, 130, 120, 150),
+                egui::Color32::from_rgba_unmultiplied(230, 210, 120, 150),
+                egui::Color32::from_rgba_unmultiplied(170, 230, 140, 150),
+            ],
         }
     }
 }
@@ -5,6 +5,7 @@ pub fn add_controls<F: audio_utils::Float + egui::emath::Numeric, const NUM_BAND
     ui: &mut egui::Ui,
     size: egui::Vec2,
     params: &mut Params<F, NUM_BANDS>,
+    chain: &EqChain<F, 0>,
     spectrum_available: bool,
     eq_ranges: &app_lib::settings::ui::EqRanges<F>,
     eq_colors: &[egui::Color32],
@@ -26,43 +27,120 @@ pub fn add_controls<F: audio_utils::Float + egui::emath::Numeric, const NUM_BAND
                                         &mut show_options.signal_gain_spectrum,
                                         "Analyze Signal",
                                     );
+                                    ui.checkbox(
+                                        &mut show_options.sidechain_gain_spectrum,
+                                        "Sidechain",
+                                    );
                                 }
+                                ui.checkbox(&mut show_options.analog_gain, "Analog");
                             });
                         } else {
                             ui.checkbox(&mut show_options.gain, "Gain");
                         }
                         ui.checkbox(&mut show_options.phase, "Phase");
+                        ui.checkbox(&mut show_options.group_delay, "Group Delay");
                         ui.checkbox(&mut show_options.impulse_response, "Impulse Response");
+                        ui.checkbox(&mut show_options.step_response, "Step Response");
+                        ui.checkbox(&mut show_options.cumulative_energy, "Cumulative Energy");
                         ui.checkbox(&mut show_options.poles_and_zeros, "Poles And Zeros");
                     });
-                    for (index, eq) in params.eqs.iter_mut().enumerate() {
+                    if let Some(output) = &mut params.output {
+                        control::output::add_control(ui, output, eq_ranges);
+                    }
+                    add_design_control(ui, control_width, &mut params.eqs);
+                    for (index, ((eq, band_routing), dynamics)) in params
+                        .eqs
+                        .iter_mut()
+                        .zip(params.routings.iter_mut())
+                        .zip(params.dynamics.iter_mut())
+                        .enumerate()
+                    {
+                        // only a processed signal can be auditioned
+                        let mut solo = params.solo_band == Some(index);
+                        // bands that can't be designed are highlighted with the reason, where
+                        // the signal is processed or else here
+                        let error = params
+                            .band_errors
+                            .and_then(|errors| errors[index])
+                            .or_else(|| chain.error(index).map(|error| error.cast()));
                         add_control(
                             ui,
                             control_width,
                             control_outer_margin,
                             eq_colors[index % eq_colors.len()],
                             eq,
+                            band_routing,
+                            dynamics,
+                            spectrum_available.then_some(&mut solo),
+                            error,
                             eq_ranges,
                         );
+                        if solo != (params.solo_band == Some(index)) {
+                            params.solo_band = solo.then_some(index);
+                        }
                     }
                 });
             });
     });
 }
 
+/// Sets the design of all bands at once
+fn add_design_control<F: audio_utils::Float>(ui: &mut egui::Ui, width: f32, eqs: &mut [eq::Eq<F>]) {
+    let common_design = eqs
+        .first()
+        .map(|eq| eq.design)
+        .filter(|design| eqs.iter().all(|eq| eq.design == *design));
+    let mut selected_design = common_design;
+    egui::ComboBox::from_id_salt(ui.next_auto_id())
+        .selected_text(format!(
+            "Design: {}",
+            selected_design
+                .as_ref()
+                .map_or("Mixed", eq::Design::to_string)
+        ))
+        .width(width)
+        .show_ui(ui, |ui| {
+            for design in eq::Design::ALL.iter() {
+                ui.selectable_value(&mut selected_design, Some(*design), design.to_string());
+            }
+        });
+    if selected_design != common_design
+        && let Some(design) = selected_design
+    {
+        for eq in eqs.iter_mut() {
+            eq.design = design;
+        }
+    }
+}
+
 fn add_control<F: audio_utils::Float + egui::emath::Numeric>(
     ui: &mut egui::Ui,
     width: f32,
     outer_margin: f32,
     color: egui::Color32,
     eq: &mut eq::Eq<F>,
+    band_routing: &mut routing::Routing,
+    dynamics: &mut dynamic_eq::Dynamics<F>,
+    solo: Option<&mut bool>,
+    error: Option<biquad::coefficients::Error<F>>,
     eq_ranges: &app_lib::settings::ui::EqRanges<F>,
 ) {
     let mut gain_db = eq.gain.db();
     let mut log_frequency = eq.frequency.log_hz();
+    let error_color = ui.visuals().error_fg_color;
+    let opacity = 0.2_f32;
+    let stroke = match error {
+        // set after multiply_with_opacity, so it stays fully opaque
+        Some(_) => egui::Stroke::new(2_f32, error_color),
+        None => {
+            let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+            egui::Stroke::new(stroke.width, stroke.color.gamma_multiply(opacity))
+        }
+    };
     egui::Frame::group(ui.style())
         .fill(color)
-        .multiply_with_opacity(0.2_f32)
+        .multiply_with_opacity(opacity)
+        .stroke(stroke)
         .corner_radius(5)
         .outer_margin(outer_margin)
         .show(ui, |ui| {
@@ -75,6 +153,9 @@ fn add_control<F: audio_utils::Float + egui::emath::Numeric>(
                             ui.selectable_value(&mut eq.eq_type, *eq_type, eq_type.to_string());
                         }
                     });
+                if !eq.eq_type.has_bandwidth_unit(eq.bandwidth.unit()) {
+                    eq.bandwidth = eq.bandwidth.to_unit(eq::BandwidthUnit::Q, eq.gain.db());
+                }
 
                 if eq.eq_type.has_frequency() {
                     ui.add(
@@ -102,8 +183,186 @@ fn add_control<F: audio_utils::Float + egui::emath::Numeric>(
                 }
 
                 if eq.eq_type.has_q() {
-                    ui.add(egui::Slider::new(&mut eq.q, eq_ranges.q_range.clone()).prefix("Q: "));
+                    add_bandwidth_control(ui, width, eq, eq_ranges);
+                }
+
+                if eq.eq_type.has_peak_shape() {
+                    egui::ComboBox::from_id_salt(ui.next_auto_id())
+                        .selected_text(eq.peak_shape.to_string())
+                        .width(width)
+                        .show_ui(ui, |ui| {
+                            for peak_shape in eq::PeakShape::ALL.iter() {
+                                ui.selectable_value(
+                                    &mut eq.peak_shape,
+                                    *peak_shape,
+                                    peak_shape.to_string(),
+                                );
+                            }
+                        });
+                }
+
+                if eq.eq_type.has_slope() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(ui.next_auto_id())
+                            .selected_text(eq.slope.to_string())
+                            .width(0.5_f32 * width)
+                            .show_ui(ui, |ui| {
+                                for slope in eq::Slope::ALL.iter() {
+                                    ui.selectable_value(&mut eq.slope, *slope, slope.to_string());
+                                }
+                            });
+                        // odd slopes are Butterworth only, the choice is kept for the others
+                        let alignment = eq.alignment.for_slope(eq.slope);
+                        let has_alignments = eq::Alignment::LinkwitzRiley.for_slope(eq.slope)
+                            == eq::Alignment::LinkwitzRiley;
+                        ui.add_enabled_ui(has_alignments, |ui| {
+                            egui::ComboBox::from_id_salt(ui.next_auto_id())
+                                .selected_text(alignment.to_string())
+                                .width(0.5_f32 * width)
+                                .show_ui(ui, |ui| {
+                                    for alignment in eq::Alignment::ALL.iter() {
+                                        ui.selectable_value(
+                                            &mut eq.alignment,
+                                            *alignment,
+                                            alignment.to_string(),
+                                        );
+                                    }
+                                });
+                        });
+                    });
+                }
+
+                if eq.eq_type.has_frequency() {
+                    egui::ComboBox::from_id_salt(ui.next_auto_id())
+                        .selected_text(eq.design.to_string())
+                        .width(width)
+                        .show_ui(ui, |ui| {
+                            for design in eq::Design::ALL.iter() {
+                                ui.selectable_value(&mut eq.design, *design, design.to_string());
+                            }
+                        });
                 }
+
+                if eq.eq_type.is_active() {
+                    egui::ComboBox::from_id_salt(ui.next_auto_id())
+                        .selected_text(format!("Routing: {}", band_routing.to_string()))
+                        .width(width)
+                        .show_ui(ui, |ui| {
+                            for available_routing in routing::Routing::ALL.iter() {
+                                ui.selectable_value(
+                                    band_routing,
+                                    *available_routing,
+                                    available_routing.to_string(),
+                                );
+                            }
+                        });
+                }
+
+                if let Some(solo) = solo {
+                    ui.toggle_value(solo, "Solo").on_hover_text(
+                        "Hear the band-pass at the frequency and bandwidth of the band",
+                    );
+                }
+
+                if eq.eq_type.has_gain_db() {
+                    add_dynamics_control(ui, dynamics, eq_ranges);
+                }
+
+                if let Some(error) = &error {
+                    ui.colored_label(error_color, error.to_string());
+                }
+            });
+        });
+}
+
+fn add_dynamics_control<F: audio_utils::Float + egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    dynamics: &mut dynamic_eq::Dynamics<F>,
+    eq_ranges: &app_lib::settings::ui::EqRanges<F>,
+) {
+    egui::CollapsingHeader::new("Dynamics")
+        .id_salt(ui.next_auto_id())
+        .show(ui, |ui| {
+            ui.checkbox(&mut dynamics.enabled, "Dynamic");
+            ui.add_enabled_ui(dynamics.enabled, |ui| {
+                ui.checkbox(&mut dynamics.sidechain, "Sidechain");
+                ui.add(
+                    egui::Slider::new(
+                        &mut dynamics.threshold_db,
+                        eq_ranges.threshold_db_range.clone(),
+                    )
+                    .prefix("threshold: ")
+                    .suffix("dB"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut dynamics.ratio, eq_ranges.ratio_range.clone())
+                        .logarithmic(true)
+                        .prefix("ratio: "),
+                );
+                ui.add(
+                    egui::Slider::new(
+                        &mut dynamics.range_db,
+                        eq_ranges.dynamic_range_db_range.clone(),
+                    )
+                    .prefix("range: ")
+                    .suffix("dB"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut dynamics.attack_ms, eq_ranges.attack_ms_range.clone())
+                        .logarithmic(true)
+                        .prefix("attack: ")
+                        .suffix("ms"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut dynamics.release_ms, eq_ranges.release_ms_range.clone())
+                        .logarithmic(true)
+                        .prefix("release: ")
+                        .suffix("ms"),
+                );
             });
         });
 }
+
+fn add_bandwidth_control<F: audio_utils::Float + egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    width: f32,
+    eq: &mut eq::Eq<F>,
+    eq_ranges: &app_lib::settings::ui::EqRanges<F>,
+) {
+    ui.horizontal(|ui| {
+        let mut unit = eq.bandwidth.unit();
+        egui::ComboBox::from_id_salt(ui.next_auto_id())
+            .selected_text(unit.to_string())
+            .width(0.3_f32 * width)
+            .show_ui(ui, |ui| {
+                for available_unit in eq::BandwidthUnit::ALL
+                    .iter()
+                    .filter(|unit| eq.eq_type.has_bandwidth_unit(**unit))
+                {
+                    ui.selectable_value(&mut unit, *available_unit, available_unit.to_string());
+                }
+            });
+        if unit != eq.bandwidth.unit() {
+            eq.bandwidth = eq.bandwidth.to_unit(unit, eq.gain.db());
+        }
+
+        let gain_db = eq.gain.db();
+        match &mut eq.bandwidth {
+            eq::Bandwidth::Q(q) => ui.add(egui::Slider::new(q, eq_ranges.q_range.clone())),
+            eq::Bandwidth::Octaves(octaves) => {
+                ui.add(egui::Slider::new(octaves, eq_ranges.octaves_range.clone()).suffix(" oct"))
+            }
+            eq::Bandwidth::ShelfSlope(shelf_slope) => {
+                // slopes without a q in range for the gain are clamped, so that they convert
+                // back and forth without loss
+                let valid_range =
+                    eq::Bandwidth::shelf_slope_range(eq_ranges.q_range.clone(), gain_db);
+                let range = valid_range
+                    .start()
+                    .max(*eq_ranges.shelf_slope_range.start())
+                    ..=valid_range.end().min(*eq_ranges.shelf_slope_range.end());
+                ui.add(egui::Slider::new(shelf_slope, range).suffix(" S"))
+            }
+        };
+    });
+}
@@ -0,0 +1,25 @@
+use crate::*;
+
+pub fn add_control<F: audio_utils::Float + egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    output: &mut Output<F>,
+    eq_ranges: &app_lib::settings::ui::EqRanges<F>,
+) {
+    egui::CollapsingHeader::new("Output")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.toggle_value(&mut output.bypass, "Bypass");
+            ui.add_enabled_ui(!output.bypass, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut output.gain_db, eq_ranges.output_gain_db_range.clone())
+                        .prefix("gain: ")
+                        .suffix("dB"),
+                );
+                ui.horizontal(|ui| {
+                    for (index, invert) in output.invert_polarities.iter_mut().enumerate() {
+                        ui.checkbox(invert, format!("Invert {}", index + 1));
+                    }
+                });
+            });
+        });
+}
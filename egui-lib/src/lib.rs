@@ -14,8 +14,42 @@ pub struct Params<F: audio_utils::Float, const NUM_BANDS: usize> {
     pub show_options: app_lib::settings::ui::ShowOptions,
     #[serde(with = "serde_arrays")]
     pub eqs: [eq::Eq<F>; NUM_BANDS],
+    #[serde(with = "serde_arrays", default = "default_routings")]
+    pub routings: [routing::Routing; NUM_BANDS],
+    #[serde(with = "serde_arrays", default = "default_dynamics")]
+    pub dynamics: [dynamic_eq::Dynamics<F>; NUM_BANDS],
     pub sample_rate: F,
     pub drag_eq_index: usize,
+    /// Current gain changes of the dynamic bands, if something processes them
+    #[serde(skip)]
+    pub dynamic_gains_db: Option<[F; NUM_BANDS]>,
+    /// The band heard alone, if something processes the signal
+    #[serde(skip)]
+    pub solo_band: Option<usize>,
+    /// The stage after the bands, if something processes the signal
+    #[serde(skip)]
+    pub output: Option<Output<F>>,
+    /// Why bands can't be designed where the signal is processed, which might differ from the
+    /// design here, e.g. for dynamic bands, if something processes the signal
+    #[serde(skip)]
+    pub band_errors: Option<[Option<biquad::coefficients::Error<F>>; NUM_BANDS]>,
+}
+
+/// Gain, polarity per channel and bypass of a processor, applied after all bands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output<F: audio_utils::Float> {
+    pub gain_db: F,
+    pub invert_polarities: Vec<bool>,
+    pub bypass: bool,
+}
+
+fn default_routings<const NUM_BANDS: usize>() -> [routing::Routing; NUM_BANDS] {
+    [routing::Routing::default(); NUM_BANDS]
+}
+
+fn default_dynamics<F: audio_utils::Float, const NUM_BANDS: usize>()
+-> [dynamic_eq::Dynamics<F>; NUM_BANDS] {
+    std::array::from_fn(|_| dynamic_eq::Dynamics::default())
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +62,8 @@ pub struct SpectrumData<'a, F: audio_utils::Float, const NUM_BINS: usize, const
 {
     pub frequency_bins: &'a fft::LogFrequencyRangeBins<F, NUM_BINS>,
     pub linear_gains: &'a [[F; NUM_BINS]; NUM_CHANNELS],
+    /// Of the sidechain input, in the same bins
+    pub sidechain_linear_gains: Option<&'a [[F; NUM_BINS]; NUM_CHANNELS]>,
 }
 
 pub fn draw<
@@ -42,6 +78,9 @@ pub fn draw<
     spectrum_data: &Option<SpectrumData<F, NUM_SPECTRUM_BINS, NUM_SPECTRUM_CHANNELS>>,
 ) {
     let ui_size = ui.available_size();
+    // designs the bands for the errors in the controls, then again where they were edited
+    // for the plots
+    let mut chain = EqChain::<F, 0>::new(params.eqs.iter().cloned(), params.sample_rate);
 
     ui.horizontal(|ui| {
         let control_width = 250_f32;
@@ -49,6 +88,7 @@ pub fn draw<
             ui,
             egui::Vec2::new(control_width, ui_size.y),
             params,
+            &chain,
             spectrum_data.is_some(),
             &settings.app.eq_ranges,
             &settings.color_palette.eq_stroke,
@@ -56,12 +96,19 @@ pub fn draw<
 
         if !(params.show_options.gain
             || params.show_options.phase
+            || params.show_options.group_delay
             || params.show_options.impulse_response
+            || params.show_options.step_response
+            || params.show_options.cumulative_energy
             || params.show_options.poles_and_zeros)
         {
             return;
         }
+        for (band, (eq, band_routing)) in params.eqs.iter().zip(params.routings).enumerate() {
+            chain.set_eq(band, eq);
+            chain.set_routing(band, band_routing);
+        }
         let available_size = egui::Vec2::new(0.96_f32 * (ui_size.x - control_width), ui_size.y);
-        plotter::add_plots(ui, &available_size, params, settings, spectrum_data);
+        plotter::add_plots(ui, &available_size, params, &chain, settings, spectrum_data);
     });
 }
@@ -17,7 +17,10 @@ pub fn add_plot<
     const NUM_SPECTRUM_CHANNELS: usize,
 >(
     ui: &mut egui::Ui,
-    coefficients: &[Option<biquad::coefficients::Coefficients<F>>],
+    coefficients: &[Option<biquad::cascade::Coefficients<F>>],
+    routings: &[routing::Routing],
+    analog_cascades: &[Option<biquad::analog::AnalogCascade<F>>],
+    dynamic_coefficients: &[Option<biquad::cascade::Coefficients<F>>],
     sample_rate: F,
     last_drag_eq_index: usize,
     eq_ranges: &app_lib::settings::ui::EqRanges<F>,
@@ -77,21 +80,52 @@ pub fn add_plot<
         ));
 
         if let Some(spectrum_data) = spectrum_data.as_ref() {
-            let spectrum_rectangles =
-                make_spectrum_rectangles(spectrum_data, &log_frequency_range, &db_range);
-            for rectangle in spectrum_rectangles {
-                let plot_points = egui_plot::PlotPoints::new(rectangle);
-                plot_ui.polygon(
-                    egui_plot::Polygon::new("", plot_points)
-                        .width(1_f32)
-                        .fill_color(color_palette.spectrum_fill)
-                        .stroke(egui::Stroke::new(1_f32, color_palette.spectrum_fill)),
+            let linear_gains = [
+                (
+                    Some(spectrum_data.linear_gains),
+                    color_palette.spectrum_fill,
+                ),
+                (
+                    spectrum_data.sidechain_linear_gains,
+                    color_palette.sidechain_spectrum_fill,
+                ),
+            ];
+            for (linear_gains, fill_color) in linear_gains {
+                let Some(linear_gains) = linear_gains else {
+                    continue;
+                };
+                let spectrum_rectangles = make_spectrum_rectangles(
+                    spectrum_data.frequency_bins,
+                    linear_gains,
+                    &log_frequency_range,
+                    &db_range,
                 );
+                for rectangle in spectrum_rectangles {
+                    let plot_points = egui_plot::PlotPoints::new(rectangle);
+                    plot_ui.polygon(
+                        egui_plot::Polygon::new("", plot_points)
+                            .width(1_f32)
+                            .fill_color(fill_color)
+                            .stroke(egui::Stroke::new(1_f32, fill_color)),
+                    );
+                }
             }
         }
 
+        // with bands routed to parts of the stereo signal, each target gets its own multiband
+        // curve, of its bands together with the stereo ones
+        let routing_targets = routing::Routing::ALL
+            .iter()
+            .filter(|target| {
+                **target != routing::Routing::Stereo
+                    && coefficients
+                        .iter()
+                        .zip(routings)
+                        .any(|(c, band_routing)| c.is_some() && band_routing == *target)
+            })
+            .collect::<Vec<_>>();
         let active_coefficients = coefficients.iter().filter(|c| c.is_some());
-        if active_coefficients.clone().take(2).count() > 1 {
+        if routing_targets.is_empty() && active_coefficients.clone().take(2).count() > 1 {
             let multiband_frequency_response = biquad::utils::multiband::make_frequency_response(
                 active_coefficients.map(|c| c.as_ref().unwrap().clone()),
                 sample_rate,
@@ -105,9 +139,30 @@ pub fn add_plot<
                     .color(color_palette.multiband_stroke),
             );
         }
+        for target in routing_targets {
+            let target = *target;
+            let target_coefficients = coefficients
+                .iter()
+                .zip(routings)
+                .filter(move |(_, band_routing)| {
+                    **band_routing == routing::Routing::Stereo || **band_routing == target
+                })
+                .filter_map(|(c, _)| c.clone());
+            let multiband_frequency_response =
+                biquad::utils::multiband::make_frequency_response(target_coefficients, sample_rate);
+            let gain_points = utils::make_log_frequency_points(
+                audio_utils::make_gain_db_response(multiband_frequency_response),
+                log_frequency_range,
+            );
+            plot_ui.line(
+                egui_plot::Line::new(format!("multiband ({})", target.to_string()), gain_points)
+                    .color(color_palette.routing_stroke[target as usize - 1]),
+            );
+        }
         for (index, c) in coefficients.iter().enumerate() {
             if let Some(c) = c {
-                let response = biquad::utils::make_frequency_response(c.clone(), sample_rate);
+                let response =
+                    biquad::utils::cascade::make_frequency_response(c.clone(), sample_rate);
                 let gain_points = utils::make_log_frequency_points(
                     audio_utils::make_gain_db_response(response),
                     log_frequency_range,
@@ -121,6 +176,71 @@ pub fn add_plot<
             }
         }
 
+        if dynamic_coefficients.iter().any(|d| d.is_some()) {
+            // the dynamic bands at their current gain, the others at their static one
+            let multiband_frequency_response = biquad::utils::multiband::make_frequency_response(
+                coefficients
+                    .iter()
+                    .zip(dynamic_coefficients.iter())
+                    .filter_map(|(c, d)| d.as_ref().or(c.as_ref()).cloned()),
+                sample_rate,
+            );
+            let gain_points = utils::make_log_frequency_points(
+                audio_utils::make_gain_db_response(multiband_frequency_response),
+                log_frequency_range,
+            );
+            plot_ui.line(
+                egui_plot::Line::new("multiband (dynamic)", gain_points)
+                    .color(color_palette.multiband_stroke)
+                    .style(egui_plot::LineStyle::Dotted { spacing: 5_f32 }),
+            );
+        }
+        for (index, d) in dynamic_coefficients.iter().enumerate() {
+            if let Some(d) = d {
+                let response =
+                    biquad::utils::cascade::make_frequency_response(d.clone(), sample_rate);
+                let gain_points = utils::make_log_frequency_points(
+                    audio_utils::make_gain_db_response(response),
+                    log_frequency_range,
+                );
+                plot_ui.line(
+                    egui_plot::Line::new("", gain_points)
+                        .color(color_palette.eq_stroke[index % color_palette.eq_stroke.len()])
+                        .style(egui_plot::LineStyle::Dotted { spacing: 5_f32 }),
+                );
+            }
+        }
+
+        let active_analog_cascades = analog_cascades.iter().filter(|a| a.is_some());
+        if active_analog_cascades.clone().take(2).count() > 1 {
+            let multiband_frequency_response = biquad::analog::multiband::make_frequency_response(
+                active_analog_cascades.map(|a| a.as_ref().unwrap().clone()),
+            );
+            let gain_points = utils::make_log_frequency_points(
+                audio_utils::make_gain_db_response(multiband_frequency_response),
+                log_frequency_range,
+            );
+            plot_ui.line(
+                egui_plot::Line::new("multiband (analog)", gain_points)
+                    .color(color_palette.multiband_stroke)
+                    .style(egui_plot::LineStyle::Dashed { length: 5_f32 }),
+            );
+        }
+        for (index, a) in analog_cascades.iter().enumerate() {
+            if let Some(a) = a {
+                let response = biquad::analog::make_frequency_response(a.clone());
+                let gain_points = utils::make_log_frequency_points(
+                    audio_utils::make_gain_db_response(response),
+                    log_frequency_range,
+                );
+                plot_ui.line(
+                    egui_plot::Line::new("", gain_points)
+                        .color(color_palette.eq_stroke[index % color_palette.eq_stroke.len()])
+                        .style(egui_plot::LineStyle::Dashed { length: 5_f32 }),
+                );
+            }
+        }
+
         plot_ui.pointer_coordinate_drag_delta()
     });
 
@@ -159,11 +279,12 @@ fn make_spectrum_rectangles<
     const NUM_SPECTRUM_BINS: usize,
     const NUM_SPECTRUM_CHANNELS: usize,
 >(
-    spectrum_data: &plotter::SpectrumData<F, NUM_SPECTRUM_BINS, NUM_SPECTRUM_CHANNELS>,
+    frequency_bins: &fft::LogFrequencyRangeBins<F, NUM_SPECTRUM_BINS>,
+    linear_gains: &[[F; NUM_SPECTRUM_BINS]; NUM_SPECTRUM_CHANNELS],
     log_frequency_range: &std::ops::RangeInclusive<F>,
     db_range: &std::ops::RangeInclusive<F>,
 ) -> Vec<Vec<[f64; 2]>> {
-    let bins = &spectrum_data.frequency_bins.bins();
+    let bins = &frequency_bins.bins();
     let mut rectangles: Vec<Vec<[f64; 2]>> =
         Vec::with_capacity(NUM_SPECTRUM_BINS * NUM_SPECTRUM_CHANNELS);
 
@@ -192,7 +313,7 @@ fn make_spectrum_rectangles<
         ])
     };
 
-    for channel_gains in spectrum_data.linear_gains.iter() {
+    for channel_gains in linear_gains.iter() {
         for i in 0..NUM_SPECTRUM_BINS {
             if let Some(rect) =
                 bin_rectangle(&bins[i], audio_utils::amplitude_to_db(channel_gains[i]))
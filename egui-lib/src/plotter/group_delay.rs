@@ -0,0 +1,90 @@
+use crate::*;
+use audio_lib::utils as audio_utils;
+
+pub fn add_plot<F: audio_utils::Float + egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    coefficients: &[Option<biquad::cascade::Coefficients<F>>],
+    sample_rate: F,
+    log_frequency_range: &std::ops::RangeInclusive<F>,
+    plot_size: f32,
+    color_palette: &colors::ColorPalette,
+) {
+    egui_plot::Plot::new("Group Delay")
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .width(plot_size)
+        .height(plot_size)
+        .auto_bounds([false, true])
+        .custom_x_axes(vec![
+            egui_plot::AxisHints::new_x()
+                .formatter(utils::log_frequency_formatter)
+                .placement(egui_plot::VPlacement::Top),
+            egui_plot::AxisHints::new_x()
+                .label("Frequency (Hz)")
+                .formatter(|_, _| String::new()),
+        ])
+        .custom_y_axes(vec![
+            egui_plot::AxisHints::new_y()
+                .label("Group Delay (ms)")
+                .formatter(|_, _| String::new()),
+            egui_plot::AxisHints::new_y().placement(egui_plot::HPlacement::Right),
+        ])
+        .label_formatter(|_, point| {
+            format!(
+                "{} Hz, {:.3} ms",
+                audio_utils::log_to_frequency(point.x) as i32,
+                point.y
+            )
+        })
+        .show_x(false)
+        .legend(egui_plot::Legend::default())
+        .show(ui, |plot_ui| {
+            plot_ui.set_plot_bounds_x(utils::range_to_f64(log_frequency_range));
+
+            let active_coefficients = coefficients.iter().filter(|c| c.is_some());
+            if active_coefficients.clone().take(2).count() > 1 {
+                let multiband_group_delay_response =
+                    biquad::utils::multiband::make_group_delay_response(
+                        active_coefficients.map(|c| c.as_ref().unwrap().clone()),
+                        sample_rate,
+                    );
+                let group_delay_points = utils::make_log_frequency_points(
+                    to_milliseconds(multiband_group_delay_response),
+                    log_frequency_range,
+                );
+                plot_ui.line(
+                    egui_plot::Line::new("multiband", group_delay_points)
+                        .color(color_palette.multiband_stroke),
+                );
+            }
+            for (index, c) in coefficients.iter().enumerate() {
+                if let Some(c) = c {
+                    let response =
+                        biquad::utils::cascade::make_group_delay_response(c.clone(), sample_rate);
+                    let group_delay_points = utils::make_log_frequency_points(
+                        to_milliseconds(response),
+                        log_frequency_range,
+                    );
+                    plot_ui.line(
+                        egui_plot::Line::new("", group_delay_points)
+                            .color(color_palette.eq_stroke[index % color_palette.eq_stroke.len()]),
+                    );
+                }
+            }
+        });
+}
+
+/// Delays are infinite at zeros on the unit circle, which would break the automatic bounds
+fn to_milliseconds<F: audio_utils::Float>(
+    group_delay_response: impl Fn(F) -> F,
+) -> impl Fn(F) -> F {
+    move |frequency| {
+        let group_delay_ms = group_delay_response(frequency) * F::from(1000).unwrap();
+        if group_delay_ms.is_finite() {
+            group_delay_ms
+        } else {
+            F::nan()
+        }
+    }
+}
@@ -2,7 +2,7 @@ use crate::*;
 
 pub fn add_plot<F: audio_utils::Float + egui::emath::Numeric>(
     ui: &mut egui::Ui,
-    coefficients: &[Option<biquad::coefficients::Coefficients<F>>],
+    coefficients: &[Option<biquad::cascade::Coefficients<F>>],
     impulse_response_params: &app_lib::settings::ui::ImpulseResponseParams<F>,
     plot_size: f32,
     color_palette: &colors::ColorPalette,
@@ -54,12 +54,13 @@ pub fn add_plot<F: audio_utils::Float + egui::emath::Numeric>(
             }
             for (index, c) in coefficients.iter().enumerate() {
                 if let Some(c) = c {
-                    let impulse_response = biquad::utils::impulse_response_for_coefficients(
-                        c.clone(),
-                        impulse_response_params.eps,
-                        impulse_response_params.hold_length,
-                        impulse_response_params.max_length,
-                    );
+                    let impulse_response =
+                        biquad::utils::cascade::impulse_response_for_coefficients(
+                            c.clone(),
+                            impulse_response_params.eps,
+                            impulse_response_params.hold_length,
+                            impulse_response_params.max_length,
+                        );
                     plot_ui.line(
                         egui_plot::Line::new("", to_plot_points(impulse_response))
                             .color(color_palette.eq_stroke[index % color_palette.eq_stroke.len()]),
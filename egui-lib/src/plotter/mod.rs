@@ -1,7 +1,10 @@
+mod cumulative_energy;
 mod gain;
+mod group_delay;
 mod impulse_response;
 mod phase;
 mod poles_and_zeros;
+mod step_response;
 
 use crate::*;
 use audio_lib::{biquad, eq};
@@ -15,6 +18,7 @@ pub fn add_plots<
     ui: &mut egui::Ui,
     available_size: &egui::Vec2,
     params: &mut Params<F, NUM_BANDS>,
+    chain: &EqChain<F, 0>,
     settings: &Settings<F>,
     spectrum_data: &Option<SpectrumData<F, NUM_SPECTRUM_BINS, NUM_SPECTRUM_CHANNELS>>,
 ) {
@@ -25,20 +29,47 @@ pub fn add_plots<
     }
     let drag_eq_index = &mut params.drag_eq_index;
     let sample_rate = params.sample_rate;
-    let coefficients = params
-        .eqs
-        .iter()
-        .map(|eq| {
-            if eq.eq_type.is_active() {
-                Some(biquad::coefficients::Coefficients::from_eq(
-                    &eq,
-                    sample_rate,
-                ))
-            } else {
-                None
-            }
-        })
+    let coefficients = (0..chain.num_bands())
+        .map(|band| chain.active_coefficients(band))
         .collect::<Vec<_>>();
+    let routings = (0..chain.num_bands())
+        .map(|band| chain.routing(band))
+        .collect::<Vec<_>>();
+    let analog_cascades = if show_options.analog_gain {
+        params
+            .eqs
+            .iter()
+            .map(|eq| {
+                if eq.eq_type.is_active() {
+                    Some(biquad::analog::AnalogCascade::from_eq(&eq))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    // the dynamic bands at their current gain, next to their static curves
+    let dynamic_coefficients = match &params.dynamic_gains_db {
+        Some(gains_db) => params
+            .eqs
+            .iter()
+            .zip(params.dynamics.iter())
+            .zip(gains_db.iter())
+            .map(|((eq, dynamics), gain_db)| {
+                if eq.eq_type.is_active() && dynamics.is_active(eq) {
+                    Some(biquad::cascade::Coefficients::from_eq(
+                        &dynamics.apply(eq, *gain_db),
+                        sample_rate,
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
     egui::Frame::group(ui.style())
         .outer_margin(0_f32)
         .show(ui, |ui| {
@@ -50,6 +81,9 @@ pub fn add_plots<
                                 gain::add_plot::<F, NUM_SPECTRUM_BINS, NUM_SPECTRUM_CHANNELS>(
                                     ui,
                                     &coefficients,
+                                    &routings,
+                                    &analog_cascades,
+                                    &dynamic_coefficients,
                                     sample_rate,
                                     *drag_eq_index,
                                     &settings.app.eq_ranges,
@@ -76,6 +110,16 @@ pub fn add_plots<
                                 &settings.color_palette,
                             );
                         }
+                        if show_options.group_delay {
+                            group_delay::add_plot(
+                                ui,
+                                &coefficients,
+                                sample_rate,
+                                &settings.app.eq_ranges.log_frequency_range,
+                                plot_size,
+                                &settings.color_palette,
+                            );
+                        }
                     });
 
                     ui.vertical(|ui| {
@@ -88,6 +132,24 @@ pub fn add_plots<
                                 &settings.color_palette,
                             );
                         }
+                        if show_options.step_response {
+                            step_response::add_plot(
+                                ui,
+                                &coefficients,
+                                &settings.app.impulse_response_params,
+                                plot_size,
+                                &settings.color_palette,
+                            );
+                        }
+                        if show_options.cumulative_energy {
+                            cumulative_energy::add_plot(
+                                ui,
+                                &coefficients,
+                                &settings.app.impulse_response_params,
+                                plot_size,
+                                &settings.color_palette,
+                            );
+                        }
                         if show_options.poles_and_zeros {
                             poles_and_zeros::add_plot(
                                 ui,
@@ -106,13 +168,24 @@ fn plot_size(
     show_options: &app_lib::settings::ui::ShowOptions,
     available_size: &egui::Vec2,
 ) -> f32 {
-    let num_rows = (((show_options.gain && show_options.phase)
-        || (show_options.impulse_response && show_options.poles_and_zeros))
-        as usize
-        + 1) as f32;
-    let num_columns = (((show_options.gain || show_options.phase)
-        && (show_options.impulse_response || show_options.poles_and_zeros))
-        as usize
-        + 1) as f32;
+    let num_left_plots = [
+        show_options.gain,
+        show_options.phase,
+        show_options.group_delay,
+    ]
+    .into_iter()
+    .filter(|show| *show)
+    .count();
+    let num_right_plots = [
+        show_options.impulse_response,
+        show_options.step_response,
+        show_options.cumulative_energy,
+        show_options.poles_and_zeros,
+    ]
+    .into_iter()
+    .filter(|show| *show)
+    .count();
+    let num_rows = num_left_plots.max(num_right_plots).max(1) as f32;
+    let num_columns = ((num_left_plots > 0 && num_right_plots > 0) as usize + 1) as f32;
     (available_size.x / num_columns).min(available_size.y / num_rows) - 15_f32
 }
@@ -3,7 +3,7 @@ use audio_lib::utils as audio_utils;
 
 pub fn add_plot<F: audio_utils::Float + egui::emath::Numeric>(
     ui: &mut egui::Ui,
-    coefficients: &[Option<biquad::coefficients::Coefficients<F>>],
+    coefficients: &[Option<biquad::cascade::Coefficients<F>>],
     sample_rate: F,
     log_frequency_range: &std::ops::RangeInclusive<F>,
     plot_size: f32,
@@ -69,7 +69,8 @@ pub fn add_plot<F: audio_utils::Float + egui::emath::Numeric>(
             }
             for (index, c) in coefficients.iter().enumerate() {
                 if let Some(c) = c {
-                    let response = biquad::utils::make_frequency_response(c.clone(), sample_rate);
+                    let response =
+                        biquad::utils::cascade::make_frequency_response(c.clone(), sample_rate);
                     let phase_points = utils::make_log_frequency_points(
                         audio_utils::make_phase_response(response),
                         log_frequency_range,
@@ -36,8 +36,14 @@ impl<const NUM_BANDS: usize> EqPlotter<NUM_BANDS> {
             UiParams::<NUM_BANDS> {
                 show_options: app_lib::settings::ui::ShowOptions::new_all_enabled(),
                 eqs: app_settings.init_eqs.clone(),
+                routings: [audio_lib::routing::Routing::default(); NUM_BANDS],
+                dynamics: std::array::from_fn(|_| audio_lib::dynamic_eq::Dynamics::default()),
                 sample_rate: app_settings.init_sample_rate,
                 drag_eq_index: usize::MAX,
+                dynamic_gains_db: None,
+                solo_band: None,
+                output: None,
+                band_errors: None,
             }
         };
         Self {
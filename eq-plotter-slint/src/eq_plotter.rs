@@ -1,5 +1,5 @@
 use crate::*;
-use audio_lib::eq;
+use audio_lib::{biquad, eq};
 use std::sync;
 
 slint::include_modules!();
@@ -60,13 +60,15 @@ impl EqPlotter {
 
         ui_eq.set_min_q(*eq_ranges.q_range.start());
         ui_eq.set_max_q(*eq_ranges.q_range.end());
-        ui_eq.set_q(read_eq.q);
+        ui_eq.set_q(read_eq.q());
 
         self.ui
             .set_gain_control_visible(read_eq.eq_type.has_gain_db());
         self.ui
             .set_frequency_control_visible(read_eq.eq_type.has_frequency());
         self.ui.set_q_control_visible(read_eq.eq_type.has_q());
+
+        set_ui_error(&self.ui, &read_eq, self.sample_rate);
     }
 
     fn init_ui_callbacks(
@@ -78,6 +80,7 @@ impl EqPlotter {
         ui_callbacks.on_request_set_eq_type({
             let ui_ptr = self.ui.as_weak();
             let eq = self.eq.clone();
+            let sample_rate = self.sample_rate;
             move |eq_type: slint::SharedString| {
                 let new_eq_type_option = eq::EqType::try_from(eq_type.as_str());
                 let ui = ui_ptr.unwrap();
@@ -86,6 +89,7 @@ impl EqPlotter {
                         ui.global::<Eq>()
                             .set_eq_type(new_eq_type.to_string().into());
                         eq.write().unwrap().eq_type = new_eq_type;
+                        set_ui_error(&ui, &eq.read().unwrap(), sample_rate);
                         ui.set_gain_control_visible(new_eq_type.has_gain_db());
                         ui.set_frequency_control_visible(new_eq_type.has_frequency());
                         ui.set_q_control_visible(new_eq_type.has_q());
@@ -101,15 +105,18 @@ impl EqPlotter {
         ui_callbacks.on_request_set_gain_db({
             let ui_ptr = self.ui.as_weak();
             let eq = self.eq.clone();
+            let sample_rate = self.sample_rate;
             move |gain_db: f32| {
                 let ui = ui_ptr.unwrap();
                 ui.global::<Eq>().set_gain_db(gain_db);
                 eq.write().unwrap().gain = eq::Gain::Db(gain_db);
+                set_ui_error(&ui, &eq.read().unwrap(), sample_rate);
             }
         });
         ui_callbacks.on_request_set_log_frequency({
             let ui_ptr = self.ui.as_weak();
             let eq = self.eq.clone();
+            let sample_rate = self.sample_rate;
             move |log_frequency: f32| {
                 let ui = ui_ptr.unwrap();
                 let frequency = eq::Frequency::LogHz(log_frequency);
@@ -117,15 +124,18 @@ impl EqPlotter {
                 ui_eq.set_log_frequency(frequency.hz());
                 ui_eq.set_frequency(frequency.hz());
                 eq.write().unwrap().frequency = frequency;
+                set_ui_error(&ui, &eq.read().unwrap(), sample_rate);
             }
         });
         ui_callbacks.on_request_set_q({
             let ui_ptr = self.ui.as_weak();
             let eq = self.eq.clone();
+            let sample_rate = self.sample_rate;
             move |q: f32| {
                 let ui = ui_ptr.unwrap();
                 ui.global::<Eq>().set_q(q);
-                eq.write().unwrap().q = q;
+                eq.write().unwrap().bandwidth = eq::Bandwidth::Q(q);
+                set_ui_error(&ui, &eq.read().unwrap(), sample_rate);
             }
         });
         ui_callbacks.on_render_eq_plots({
@@ -146,3 +156,11 @@ impl EqPlotter {
         });
     }
 }
+
+fn set_ui_error(ui: &EqPlotterUi, eq: &eq::Eq<f32>, sample_rate: f32) {
+    let error = match biquad::cascade::Coefficients::try_from_eq(eq, sample_rate) {
+        Ok(_) => String::new(),
+        Err(error) => error.to_string(),
+    };
+    ui.global::<Eq>().set_error(error.into());
+}
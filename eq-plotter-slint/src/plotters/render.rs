@@ -25,22 +25,26 @@ pub fn render_eq_plots(
 
     let root_area = backend.into_drawing_area();
 
-    let chart_style = style::ChartStyleData::new(&background_color, height as f64 / 2.0);
+    let chart_style = style::ChartStyleData::new(&background_color, height as f64 / 3.0);
 
     root_area
         .fill(&chart_style.colors.background)
         .expect("error filling drawing area");
-    let plot_areas = root_area.split_evenly((2, 2));
+    let plot_areas = root_area.split_evenly((3, 2));
 
-    let coefficients = biquad::coefficients::Coefficients::from_eq(eq, sample_rate);
+    let coefficients = biquad::cascade::Coefficients::from_eq(eq, sample_rate);
     let frequency_response =
-        biquad::utils::make_frequency_response(coefficients.clone(), sample_rate);
+        biquad::utils::cascade::make_frequency_response(coefficients.clone(), sample_rate);
+
+    let analog_frequency_response =
+        biquad::analog::make_frequency_response(biquad::analog::AnalogCascade::from_eq(eq));
 
     draw_gain_chart(
         &plot_areas[0],
         &chart_style,
         &eq_ranges,
         &frequency_response,
+        &analog_frequency_response,
     );
     draw_phase_chart(
         &plot_areas[2],
@@ -55,6 +59,12 @@ pub fn render_eq_plots(
         &coefficients,
     );
     draw_poles_and_zeros_chart(&plot_areas[3], &chart_style, &coefficients);
+    draw_group_delay_chart(
+        &plot_areas[4],
+        &chart_style,
+        &eq_ranges.log_frequency_range,
+        &biquad::utils::cascade::make_group_delay_response(coefficients.clone(), sample_rate),
+    );
 
     root_area.present().expect("error presenting");
 
@@ -78,6 +88,7 @@ fn draw_gain_chart<DB: DrawingBackend>(
     style: &style::ChartStyleData,
     eq_ranges: &EqRanges,
     frequency_response: &impl Fn(f32) -> num::Complex<f32>,
+    analog_frequency_response: &impl Fn(f32) -> num::Complex<f32>,
 ) {
     let log_frequency_steps = ((*eq_ranges.log_frequency_range.start())
         ..(*eq_ranges.log_frequency_range.end()))
@@ -119,6 +130,21 @@ fn draw_gain_chart<DB: DrawingBackend>(
         .draw()
         .expect("error drawing gain response secondary axes");
 
+    let map_analog_gains = |log_frequency| {
+        (
+            log_frequency,
+            utils::amplitude_to_db(
+                analog_frequency_response(utils::log_to_frequency(log_frequency)).abs(),
+            ),
+        )
+    };
+    chart
+        .draw_series(LineSeries::new(
+            log_frequency_steps.values().map(map_analog_gains),
+            &style.colors.analog_plot,
+        ))
+        .expect("error drawing analog gain points");
+
     let map_gains = |log_frequency| {
         (
             log_frequency,
@@ -194,13 +220,80 @@ fn draw_phase_chart<DB: DrawingBackend>(
         .expect("error drawing phase points");
 }
 
+fn draw_group_delay_chart<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    style: &style::ChartStyleData,
+    log_frequency_range: &std::ops::RangeInclusive<f32>,
+    group_delay_response: &impl Fn(f32) -> f32,
+) {
+    let log_frequency_steps =
+        ((*log_frequency_range.start())..(*log_frequency_range.end())).step(0.01);
+    let group_delays_ms = log_frequency_steps
+        .values()
+        .map(|log_frequency| {
+            (
+                log_frequency,
+                1000.0 * group_delay_response(utils::log_to_frequency(log_frequency)),
+            )
+        })
+        .filter(|(_, group_delay_ms)| group_delay_ms.is_finite())
+        .collect::<Vec<_>>();
+    // at least one millisecond around zero, so flat delays don't collapse the range
+    let (group_delay_min, group_delay_max) = group_delays_ms
+        .iter()
+        .fold((-0.5_f32, 0.5_f32), |(min, max), (_, group_delay_ms)| {
+            (min.min(*group_delay_ms), max.max(*group_delay_ms))
+        });
+
+    let mut chart = ChartBuilder::on(&area)
+        .margin(style.margin_size)
+        .set_all_label_area_size(style.label_area_size)
+        .caption("Group Delay", style.caption_text_style())
+        .build_cartesian_2d(
+            log_frequency_steps.range(),
+            group_delay_min..group_delay_max,
+        )
+        .expect("error creating group delay chart")
+        .set_secondary_coord(0f32..1f32, 0f32..1f32);
+
+    chart
+        .configure_mesh()
+        .x_labels(10)
+        .y_labels(10)
+        .axis_style(style.colors.text)
+        .bold_line_style(style.colors.line)
+        .light_line_style(style.colors.line)
+        .label_style(style.label_text_style())
+        .x_label_formatter(&|log_frequency| {
+            format!("{:.0}", utils::log_to_frequency(*log_frequency))
+        })
+        .y_label_formatter(&|group_delay_ms| format!("{:.2}", group_delay_ms))
+        .draw()
+        .expect("error drawing group delay mesh");
+
+    chart
+        .configure_secondary_axes()
+        .x_desc("Frequency (Hz)")
+        .y_desc("Group Delay (ms)")
+        .x_labels(0)
+        .y_labels(0)
+        .axis_style(style.colors.line)
+        .label_style(style.label_text_style())
+        .draw()
+        .expect("error drawing group delay secondary axes");
+
+    chart
+        .draw_series(LineSeries::new(group_delays_ms, &style.colors.plot))
+        .expect("error drawing group delay points");
+}
+
 fn draw_ir_chart<DB: DrawingBackend>(
     area: &DrawingArea<DB, plotters::coord::Shift>,
     style: &style::ChartStyleData,
     impulse_response_params: &ImpulseResponseParams,
-    coefficients: &biquad::coefficients::Coefficients<f32>,
+    coefficients: &biquad::cascade::Coefficients<f32>,
 ) {
-    let impulse_response = biquad::utils::impulse_response_for_coefficients(
+    let impulse_response = biquad::utils::cascade::impulse_response_for_coefficients(
         coefficients.clone(),
         impulse_response_params.eps,
         impulse_response_params.hold_length,
@@ -247,7 +340,7 @@ fn draw_ir_chart<DB: DrawingBackend>(
 fn draw_poles_and_zeros_chart<DB: DrawingBackend>(
     area: &DrawingArea<DB, plotters::coord::Shift>,
     style: &style::ChartStyleData,
-    coefficients: &biquad::coefficients::Coefficients<f32>,
+    coefficients: &biquad::cascade::Coefficients<f32>,
 ) {
     let mut chart = ChartBuilder::on(&area)
         .margin(style.margin_size)
@@ -286,7 +379,7 @@ fn draw_poles_and_zeros_chart<DB: DrawingBackend>(
         ))
         .expect("error drawing unit circle");
 
-    let poles = biquad::utils::poles(&coefficients);
+    let poles = biquad::utils::cascade::poles(&coefficients);
     if !poles.is_empty() {
         let poles_series = chart
             .draw_series(PointSeries::of_element(
@@ -301,7 +394,7 @@ fn draw_poles_and_zeros_chart<DB: DrawingBackend>(
             .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
     }
 
-    let zeros = biquad::utils::zeros(&coefficients);
+    let zeros = biquad::utils::cascade::zeros(&coefficients);
     if !zeros.is_empty() {
         let zeros_series = chart
             .draw_series(PointSeries::of_element(
@@ -5,6 +5,7 @@ pub struct ChartColors {
     pub text: RGBAColor,
     pub line: RGBAColor,
     pub plot: RGBColor,
+    pub analog_plot: RGBAColor,
 }
 
 pub struct ChartFonts {
@@ -42,6 +43,7 @@ impl ChartStyleData {
                 )
                 .mix(0.05),
                 plot: RGBColor(255, 100, 0),
+                analog_plot: RGBColor(255, 100, 0).mix(0.4),
             },
             fonts: ChartFonts {
                 caption: FontDesc::new(FontFamily::SansSerif, 1.2 * label_size, FontStyle::Bold),
@@ -7,6 +7,7 @@ pub type Coefficients = fft::signal_analyzer::Coefficients<f32>;
 pub struct Analyzer<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const NUM_BINS: usize> {
     plugin_params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, NUM_BINS>>,
     analyzer: fft::SignalAnalyzer<f32, { NUM_BINS }, { NUM_CHANNELS }>,
+    sidechain_analyzer: fft::SignalAnalyzer<f32, { NUM_BINS }, { NUM_CHANNELS }>,
 }
 
 impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const NUM_BINS: usize>
@@ -19,6 +20,7 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const NUM_BINS: usize>
         Self {
             plugin_params,
             analyzer: fft::SignalAnalyzer::new(coefficients),
+            sidechain_analyzer: fft::SignalAnalyzer::new(coefficients),
         }
     }
 
@@ -28,31 +30,60 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const NUM_BINS: usize>
             .sample_rate
             .load(atomic::Ordering::Relaxed);
         self.plugin_params.analyzer_data.reset(sample_rate);
+        self.plugin_params
+            .sidechain_analyzer_data
+            .reset(sample_rate);
         self.analyzer.reset_sample_rate(sample_rate);
+        self.sidechain_analyzer.reset_sample_rate(sample_rate);
         true
     }
 
-    pub fn process(&mut self, buffer: &nice::Buffer) {
-        let analyzer_data = &self.plugin_params.analyzer_data;
+    pub fn process(&mut self, buffer: &nice::Buffer, sidechain: Option<&nice::Buffer>) {
+        let is_editor_open = self.plugin_params.editor_state.is_open();
+        let show_params = &self.plugin_params.show_params;
+        push(
+            &mut self.analyzer,
+            &self.plugin_params.analyzer_data,
+            Some(buffer).filter(|_| {
+                is_editor_open
+                    && show_params
+                        .signal_gain_spectrum
+                        .load(atomic::Ordering::Relaxed)
+            }),
+            buffer.samples(),
+        );
+        push(
+            &mut self.sidechain_analyzer,
+            &self.plugin_params.sidechain_analyzer_data,
+            sidechain.filter(|_| {
+                is_editor_open
+                    && show_params
+                        .sidechain_gain_spectrum
+                        .load(atomic::Ordering::Relaxed)
+            }),
+            buffer.samples(),
+        );
+    }
+}
 
-        if self.plugin_params.editor_state.is_open()
-            && self
-                .plugin_params
-                .show_params
-                .signal_gain_spectrum
-                .load(atomic::Ordering::Relaxed)
-        {
-            self.analyzer.push(
-                buffer.as_slice_immutable(),
-                &analyzer_data.frequency_bins.read().unwrap(),
-                &analyzer_data.linear_gains.producer,
-            );
-        } else {
-            self.analyzer.push_mute_signal(
-                buffer.samples(),
-                &analyzer_data.frequency_bins.read().unwrap(),
-                &analyzer_data.linear_gains.producer,
-            );
-        }
+/// Pushes the buffer, or silence if there is none, so that the spectrum decays
+fn push<const NUM_CHANNELS: usize, const NUM_BINS: usize>(
+    analyzer: &mut fft::SignalAnalyzer<f32, { NUM_BINS }, { NUM_CHANNELS }>,
+    analyzer_data: &fft::signal_analyzer::SharedData<f32, { NUM_BINS }, { NUM_CHANNELS }>,
+    buffer: Option<&nice::Buffer>,
+    num_samples: usize,
+) {
+    let frequency_bins = analyzer_data.frequency_bins.read().unwrap();
+    match buffer {
+        Some(buffer) => analyzer.push(
+            buffer.as_slice_immutable(),
+            &frequency_bins,
+            &analyzer_data.linear_gains.producer,
+        ),
+        None => analyzer.push_mute_signal(
+            num_samples,
+            &frequency_bins,
+            &analyzer_data.linear_gains.producer,
+        ),
     }
 }
@@ -13,8 +13,14 @@ pub fn create_editor<
     let ui_state = UiParams {
         show_options: params.show_params.load_options(),
         eqs: params.eqs(),
+        routings: params.routings(),
+        dynamics: params.dynamics(),
         sample_rate: params.sample_rate.load(atomic::Ordering::Relaxed),
         drag_eq_index: usize::MAX,
+        dynamic_gains_db: None,
+        solo_band: params.solo_band(),
+        output: Some(params.output()),
+        band_errors: None,
     };
     let min_size = egui::Vec2::new(700.0, 400.0);
 
@@ -43,11 +49,28 @@ pub fn create_editor<
                         .show_inside(ui, |ui| {
                             ui_state.eqs = params.eqs();
                             let backup_eqs = ui_state.eqs.clone();
+                            ui_state.routings = params.routings();
+                            let backup_routings = ui_state.routings;
+                            ui_state.dynamics = params.dynamics();
+                            let backup_dynamics = ui_state.dynamics;
+                            ui_state.solo_band = params.solo_band();
+                            let backup_solo_band = ui_state.solo_band;
+                            ui_state.output = Some(params.output());
+                            let backup_output = ui_state.output.clone();
+                            ui_state.dynamic_gains_db =
+                                Some(params.dynamic_gains_db.consumer.pull_and_read());
+                            ui_state.band_errors =
+                                Some(params.band_errors.consumer.pull_and_read());
                             ui_state.sample_rate =
                                 params.sample_rate.load(atomic::Ordering::Relaxed);
                             ui_state.show_options = params.show_params.load_options();
                             let spectrum_gains =
                                 params.analyzer_data.linear_gains.consumer.pull_and_read();
+                            let sidechain_spectrum_gains = params
+                                .sidechain_analyzer_data
+                                .linear_gains
+                                .consumer
+                                .pull_and_read();
                             let spectrum_data = Some(egui_lib::SpectrumData {
                                 frequency_bins: &params
                                     .analyzer_data
@@ -55,6 +78,10 @@ pub fn create_editor<
                                     .read()
                                     .unwrap(),
                                 linear_gains: &spectrum_gains,
+                                sidechain_linear_gains: ui_state
+                                    .show_options
+                                    .sidechain_gain_spectrum
+                                    .then_some(&sidechain_spectrum_gains),
                             });
                             egui_lib::draw(ui, ui_state, &ui_settings, &spectrum_data);
 
@@ -71,12 +98,63 @@ pub fn create_editor<
                                     band_params
                                         .set_log_frequency(new_eq.frequency.log_hz(), setter);
                                 }
-                                if new_eq.q != old_eq.q {
-                                    band_params.set_q(new_eq.q, setter);
+                                if new_eq.bandwidth != old_eq.bandwidth {
+                                    band_params.set_q(new_eq.q(), setter);
+                                }
+                                if new_eq.bandwidth.unit() != old_eq.bandwidth.unit() {
+                                    band_params.set_bandwidth_unit(new_eq.bandwidth.unit(), setter);
                                 }
                                 if new_eq.eq_type != old_eq.eq_type {
                                     band_params.set_eq_type(new_eq.eq_type, setter);
                                 }
+                                if new_eq.slope != old_eq.slope {
+                                    band_params.set_slope(new_eq.slope, setter);
+                                }
+                                if new_eq.alignment != old_eq.alignment {
+                                    band_params.set_alignment(new_eq.alignment, setter);
+                                }
+                                if new_eq.design != old_eq.design {
+                                    band_params.set_design(new_eq.design, setter);
+                                }
+                                if new_eq.peak_shape != old_eq.peak_shape {
+                                    band_params.set_peak_shape(new_eq.peak_shape, setter);
+                                }
+                            }
+                            for ((new_routing, old_routing), band_params) in ui_state
+                                .routings
+                                .iter()
+                                .zip(backup_routings)
+                                .zip(params.eq_params.as_ref())
+                            {
+                                if *new_routing != old_routing {
+                                    band_params.set_routing(*new_routing, setter);
+                                }
+                            }
+                            for ((new_dynamics, old_dynamics), band_params) in ui_state
+                                .dynamics
+                                .iter()
+                                .zip(backup_dynamics)
+                                .zip(params.eq_params.as_ref())
+                            {
+                                if *new_dynamics != old_dynamics {
+                                    band_params.set_dynamics(new_dynamics, setter);
+                                }
+                            }
+
+                            if ui_state.solo_band != backup_solo_band {
+                                // only one band is soloed at a time
+                                for (index, band_params) in params.eq_params.iter().enumerate() {
+                                    let solo = ui_state.solo_band == Some(index);
+                                    if solo != band_params.solo.value() {
+                                        band_params.set_solo(solo, setter);
+                                    }
+                                }
+                            }
+
+                            if let Some(output) = &ui_state.output {
+                                if Some(output) != backup_output.as_ref() {
+                                    params.set_output(output, setter);
+                                }
                             }
 
                             params.show_params.store_options(&ui_state.show_options);
@@ -1,6 +1,8 @@
 pub mod analyzer;
 pub mod config;
 pub mod editor;
+pub mod linear_phase;
+pub mod output;
 pub mod params;
 pub mod plugin;
 pub mod processor;
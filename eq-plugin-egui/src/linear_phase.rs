@@ -0,0 +1,104 @@
+use crate::*;
+use audio_lib::*;
+
+/// Odd, so the delay of the FIR is a whole number of samples
+pub const NUM_TAPS: usize = 4095;
+/// Block length of the partitioned convolution
+pub const BLOCK_LENGTH: usize = 512;
+pub const NUM_PARTITIONS: usize = NUM_TAPS.div_ceil(BLOCK_LENGTH);
+
+/// Delay of the convolution plus the delay of the linear phase FIR
+pub const LATENCY: usize = BLOCK_LENGTH + (NUM_TAPS - 1) / 2;
+
+pub type Kernel = convolution::Kernel<f32>;
+
+/// Linear phase FIR with the magnitude response of the bands, designed in f64 like the biquads
+/// of the processor. Bands with invalid parameters are left out. This allocates, so it is meant
+/// for the background task of the plugin.
+pub fn design_kernel(eqs: &[eq::Eq<f32>], sample_rate: f32) -> Kernel {
+    let sample_rate = sample_rate as f64;
+    let coefficients = eqs.iter().filter_map(|eq| {
+        biquad::cascade::Coefficients::try_from_eq(&eq::Eq::<f64>::from(eq.clone()), sample_rate)
+            .ok()
+    });
+    let taps = fir::from_coefficients(
+        coefficients,
+        sample_rate,
+        NUM_TAPS,
+        fir::Phase::Linear,
+        windows::WindowType::VonHann,
+    );
+    Kernel::new(
+        &taps.iter().map(|tap| *tap as f32).collect::<Vec<_>>(),
+        BLOCK_LENGTH,
+    )
+}
+
+pub fn make_passthrough_kernel() -> Kernel {
+    Kernel::passthrough(BLOCK_LENGTH, NUM_PARTITIONS)
+}
+
+/// Convolution of every channel with the kernel of the current band settings. Kernels are
+/// designed by the background task and taken over with a crossfade.
+pub struct LinearPhase<const NUM_BANDS: usize, const NUM_CHANNELS: usize> {
+    convolvers: [convolution::Convolver<f32>; NUM_CHANNELS],
+    requested_eqs: Option<[eq::Eq<f32>; NUM_BANDS]>,
+    requested_sample_rate: f32,
+}
+
+impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize> LinearPhase<NUM_BANDS, NUM_CHANNELS> {
+    pub fn new() -> Self {
+        let kernel = make_passthrough_kernel();
+        Self {
+            convolvers: std::array::from_fn(|_| convolution::Convolver::new(kernel.clone())),
+            requested_eqs: None,
+            requested_sample_rate: 0_f32,
+        }
+    }
+
+    /// Designs the kernel right away, since this is not on the audio thread
+    pub fn initialize(&mut self, eqs: &[eq::Eq<f32>; NUM_BANDS], sample_rate: f32) {
+        let kernel = design_kernel(eqs, sample_rate);
+        for convolver in self.convolvers.iter_mut() {
+            convolver.set_kernel(&kernel);
+            convolver.reset();
+        }
+        // kernels of earlier requests might still arrive, so the next request overrides them
+        self.requested_eqs = None;
+    }
+
+    pub fn reset(&mut self) {
+        for convolver in self.convolvers.iter_mut() {
+            convolver.reset();
+        }
+    }
+
+    /// Returns the eqs to design a new kernel for, if they changed since the last request
+    pub fn request_kernel(
+        &mut self,
+        eqs: &[eq::Eq<f32>; NUM_BANDS],
+        sample_rate: f32,
+    ) -> Option<[eq::Eq<f32>; NUM_BANDS]> {
+        if self.requested_eqs.as_ref() == Some(eqs) && self.requested_sample_rate == sample_rate {
+            return None;
+        }
+        self.requested_eqs = Some(eqs.clone());
+        self.requested_sample_rate = sample_rate;
+        self.requested_eqs.clone()
+    }
+
+    /// Takes over the newest kernel from the background task, if there is one
+    pub fn pull_kernel(&mut self, kernel_consumer: &spsc::swap::Consumer<Kernel>) {
+        if kernel_consumer.pull() {
+            kernel_consumer.consume(&mut |kernel| {
+                for convolver in self.convolvers.iter_mut() {
+                    convolver.set_kernel(kernel);
+                }
+            });
+        }
+    }
+
+    pub fn process(&mut self, channel: usize, samples: &mut [f32]) {
+        self.convolvers[channel].process(samples);
+    }
+}
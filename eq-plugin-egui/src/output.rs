@@ -0,0 +1,151 @@
+use crate::*;
+use audio_lib::*;
+use std::sync::{self, atomic};
+
+/// Output gain and polarity of the processed signal, and the bypass, which crossfades to the
+/// dry input. The dry input is delayed by the latency of the processor, so that both line up.
+pub struct Output<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS: usize>
+{
+    plugin_params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>>,
+    dry: [Vec<f32>; NUM_CHANNELS],
+    delay_lines: [Vec<f32>; NUM_CHANNELS],
+    delay_position: usize,
+    latency: usize,
+    /// Follow the inverts of the channels, from 1 to -1
+    polarities: [f32; NUM_CHANNELS],
+    /// From 0 for the processed signal to 1 for the dry input
+    bypass_mix: f32,
+    fade_step: f32,
+}
+
+impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS: usize>
+    Output<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>
+{
+    pub fn new(
+        plugin_params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>>,
+    ) -> Self {
+        Self {
+            plugin_params,
+            dry: std::array::from_fn(|_| Vec::new()),
+            delay_lines: std::array::from_fn(|_| Vec::new()),
+            delay_position: 0,
+            latency: 0,
+            polarities: [1_f32; NUM_CHANNELS],
+            bypass_mix: 0_f32,
+            fade_step: 1_f32,
+        }
+    }
+
+    /// Allocates for blocks up to max_buffer_size and the longest latency of the processor
+    pub fn initialize(&mut self, max_buffer_size: usize) -> bool {
+        let sample_rate = self
+            .plugin_params
+            .sample_rate
+            .load(atomic::Ordering::Relaxed);
+        for dry in self.dry.iter_mut() {
+            dry.resize(max_buffer_size, 0_f32);
+        }
+        for delay_line in self.delay_lines.iter_mut() {
+            delay_line.resize(linear_phase::LATENCY, 0_f32);
+        }
+        self.fade_step = 1_f32 / (Self::FADE_LENGTH_MS * 0.001 * sample_rate).max(1_f32);
+        self.reset();
+        true
+    }
+
+    /// Jumps to the current settings, without fading
+    pub fn reset(&mut self) {
+        for delay_line in self.delay_lines.iter_mut() {
+            delay_line.fill(0_f32);
+        }
+        self.delay_position = 0;
+        self.polarities = self.target_polarities();
+        self.bypass_mix = if self.plugin_params.bypass.value() {
+            1_f32
+        } else {
+            0_f32
+        };
+    }
+
+    /// Keeps the input of the block for the bypass, has to be called before the processor
+    pub fn store_dry(&mut self, buffer: &mut nice::Buffer, latency: usize) {
+        if latency != self.latency {
+            // the delay lines hold the input of the other latency
+            for delay_line in self.delay_lines.iter_mut() {
+                delay_line.fill(0_f32);
+            }
+            self.delay_position = 0;
+            self.latency = latency;
+        }
+        let num_samples = buffer.samples();
+        assert!(buffer.channels() <= NUM_CHANNELS);
+        for (channel, samples) in buffer.as_slice_immutable().iter().enumerate() {
+            let dry = &mut self.dry[channel][..num_samples];
+            if latency == 0 {
+                dry.copy_from_slice(samples);
+                continue;
+            }
+            let delay_line = &mut self.delay_lines[channel][..latency];
+            let mut position = self.delay_position;
+            for (dry_sample, sample) in dry.iter_mut().zip(samples.iter()) {
+                *dry_sample = delay_line[position];
+                delay_line[position] = *sample;
+                position = (position + 1) % latency;
+            }
+        }
+        if latency > 0 {
+            self.delay_position = (self.delay_position + num_samples) % latency;
+        }
+    }
+
+    /// The processor keeps running while bypassed, so that the bands are settled when fading
+    /// back in. Fully bypassed, the output is the dry input.
+    pub fn process(&mut self, buffer: &mut nice::Buffer) {
+        let target_polarities = self.target_polarities();
+        let target_bypass_mix = if self.plugin_params.bypass.value() {
+            1_f32
+        } else {
+            0_f32
+        };
+        let gain_db = &self.plugin_params.output_gain_db.smoothed;
+        let num_samples = buffer.samples();
+        let channels = buffer.as_slice();
+        for index in 0..num_samples {
+            let gain = utils::db_to_amplitude(gain_db.next());
+            self.bypass_mix = step_towards(self.bypass_mix, target_bypass_mix, self.fade_step);
+            for (channel, samples) in channels.iter_mut().enumerate() {
+                // a polarity change sweeps through silence instead of jumping
+                let polarity = &mut self.polarities[channel];
+                *polarity = step_towards(
+                    *polarity,
+                    target_polarities[channel],
+                    2_f32 * self.fade_step,
+                );
+                let processed = samples[index] * gain * *polarity;
+                samples[index] =
+                    processed + self.bypass_mix * (self.dry[channel][index] - processed);
+            }
+        }
+    }
+
+    fn target_polarities(&self) -> [f32; NUM_CHANNELS] {
+        std::array::from_fn(|channel| {
+            if self.plugin_params.polarity_params[channel].invert.value() {
+                -1_f32
+            } else {
+                1_f32
+            }
+        })
+    }
+
+    /// Short enough to feel immediate, long enough to avoid clicks
+    const FADE_LENGTH_MS: f32 = 10_f32;
+}
+
+fn step_towards(value: f32, target: f32, step: f32) -> f32 {
+    if value < target {
+        (value + step).min(target)
+    } else {
+        (value - step).max(target)
+    }
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    alignment: eq::Alignment,
+}
+
+impl From<eq::Alignment> for Wrapper {
+    fn from(alignment: eq::Alignment) -> Self {
+        Self {
+            alignment: alignment,
+        }
+    }
+}
+
+impl Into<eq::Alignment> for Wrapper {
+    fn into(self) -> eq::Alignment {
+        self.alignment
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &eq::Alignment::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.alignment as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = eq::Alignment::try_from(index);
+        match from_result {
+            Ok(alignment) => Self {
+                alignment: alignment,
+            },
+            _ => Self {
+                alignment: eq::Alignment::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
@@ -0,0 +1,48 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    bandwidth_unit: eq::BandwidthUnit,
+}
+
+impl From<eq::BandwidthUnit> for Wrapper {
+    fn from(bandwidth_unit: eq::BandwidthUnit) -> Self {
+        Self {
+            bandwidth_unit: bandwidth_unit,
+        }
+    }
+}
+
+impl Into<eq::BandwidthUnit> for Wrapper {
+    fn into(self) -> eq::BandwidthUnit {
+        self.bandwidth_unit
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &eq::BandwidthUnit::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.bandwidth_unit as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = eq::BandwidthUnit::try_from(index);
+        match from_result {
+            Ok(bandwidth_unit) => Self {
+                bandwidth_unit: bandwidth_unit,
+            },
+            _ => Self {
+                bandwidth_unit: eq::BandwidthUnit::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
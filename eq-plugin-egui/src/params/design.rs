@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    design: eq::Design,
+}
+
+impl From<eq::Design> for Wrapper {
+    fn from(design: eq::Design) -> Self {
+        Self { design: design }
+    }
+}
+
+impl Into<eq::Design> for Wrapper {
+    fn into(self) -> eq::Design {
+        self.design
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &eq::Design::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.design as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = eq::Design::try_from(index);
+        match from_result {
+            Ok(design) => Self { design: design },
+            _ => Self {
+                design: eq::Design::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
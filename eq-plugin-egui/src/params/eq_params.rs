@@ -1,5 +1,4 @@
 use super::*;
-use std::ops::RangeInclusive;
 
 #[derive(nice::Params)]
 pub struct EqParams {
@@ -12,19 +11,69 @@ pub struct EqParams {
     #[id = "q"]
     pub q: nice::FloatParam,
 
+    #[id = "bandwidth_unit"]
+    pub bandwidth_unit: bandwidth_unit::Param,
+
     #[id = "eq_type"]
     pub eq_type: eq_type::Param,
+
+    #[id = "slope"]
+    pub slope: slope::Param,
+
+    #[id = "alignment"]
+    pub alignment: alignment::Param,
+
+    #[id = "design"]
+    pub design: design::Param,
+
+    #[id = "peak_shape"]
+    pub peak_shape: peak_shape::Param,
+
+    #[id = "routing"]
+    pub routing: routing::Param,
+
+    #[id = "solo"]
+    pub solo: nice::BoolParam,
+
+    #[id = "dynamic"]
+    pub dynamic: nice::BoolParam,
+
+    #[id = "sidechain"]
+    pub sidechain: nice::BoolParam,
+
+    #[id = "threshold_db"]
+    pub threshold_db: nice::FloatParam,
+
+    #[id = "ratio"]
+    pub ratio: nice::FloatParam,
+
+    #[id = "range_db"]
+    pub range_db: nice::FloatParam,
+
+    #[id = "attack_ms"]
+    pub attack_ms: nice::FloatParam,
+
+    #[id = "release_ms"]
+    pub release_ms: nice::FloatParam,
 }
 
 impl EqParams {
     pub fn from_eq(
         names_suffix: &str,
-        eq: &eq_params::eq::Eq<f32>,
-        log_frequency_range: &RangeInclusive<f32>,
-        db_range: &RangeInclusive<f32>,
-        q_range: &RangeInclusive<f32>,
+        eq: &eq::Eq<f32>,
+        band_routing: audio_lib::routing::Routing,
+        dynamics: &dynamic_eq::Dynamics<f32>,
+        eq_ranges: &EqRanges,
         smoothing_length_ms: f32,
     ) -> Self {
+        let db_range = &eq_ranges.db_range;
+        let log_frequency_range = &eq_ranges.log_frequency_range;
+        let q_range = &eq_ranges.q_range;
+        let skewed_range = |range: &std::ops::RangeInclusive<f32>| nice::FloatRange::Skewed {
+            min: *range.start(),
+            max: *range.end(),
+            factor: nice::FloatRange::skew_factor(-1.0),
+        };
         Self {
             gain_db: nice::FloatParam::new(
                 format!("Gain (dB){names_suffix}"),
@@ -50,26 +99,124 @@ impl EqParams {
             .with_string_to_value(sync::Arc::new(egui_lib::utils::string_to_log_frequency)),
             q: nice::FloatParam::new(
                 format!("q{names_suffix}"),
-                eq.q,
+                eq.q(),
                 nice::FloatRange::Linear {
                     min: *q_range.start(),
                     max: *q_range.end(),
                 },
             )
             .with_smoother(nice::SmoothingStyle::Linear(smoothing_length_ms)),
+            bandwidth_unit: bandwidth_unit::Param::new(
+                format!("Bandwidth Unit{names_suffix}"),
+                bandwidth_unit::Wrapper::from(eq.bandwidth.unit()),
+            ),
             eq_type: eq_type::Param::new(
                 format!("Eq Type{names_suffix}"),
                 eq_type::Wrapper::from(eq.eq_type),
             ),
+            slope: slope::Param::new(
+                format!("Slope{names_suffix}"),
+                slope::Wrapper::from(eq.slope),
+            ),
+            alignment: alignment::Param::new(
+                format!("Alignment{names_suffix}"),
+                alignment::Wrapper::from(eq.alignment),
+            ),
+            design: design::Param::new(
+                format!("Design{names_suffix}"),
+                design::Wrapper::from(eq.design),
+            ),
+            peak_shape: peak_shape::Param::new(
+                format!("Peak Shape{names_suffix}"),
+                peak_shape::Wrapper::from(eq.peak_shape),
+            ),
+            routing: routing::Param::new(
+                format!("Routing{names_suffix}"),
+                routing::Wrapper::from(band_routing),
+            ),
+            solo: nice::BoolParam::new(format!("Solo{names_suffix}"), false),
+            dynamic: nice::BoolParam::new(format!("Dynamic{names_suffix}"), dynamics.enabled),
+            sidechain: nice::BoolParam::new(format!("Sidechain{names_suffix}"), dynamics.sidechain),
+            threshold_db: nice::FloatParam::new(
+                format!("Threshold (dB){names_suffix}"),
+                dynamics.threshold_db,
+                nice::FloatRange::Linear {
+                    min: *eq_ranges.threshold_db_range.start(),
+                    max: *eq_ranges.threshold_db_range.end(),
+                },
+            )
+            .with_unit(" dB"),
+            ratio: nice::FloatParam::new(
+                format!("Ratio{names_suffix}"),
+                dynamics.ratio,
+                skewed_range(&eq_ranges.ratio_range),
+            ),
+            range_db: nice::FloatParam::new(
+                format!("Range (dB){names_suffix}"),
+                dynamics.range_db,
+                nice::FloatRange::Linear {
+                    min: *eq_ranges.dynamic_range_db_range.start(),
+                    max: *eq_ranges.dynamic_range_db_range.end(),
+                },
+            )
+            .with_unit(" dB"),
+            attack_ms: nice::FloatParam::new(
+                format!("Attack (ms){names_suffix}"),
+                dynamics.attack_ms,
+                skewed_range(&eq_ranges.attack_ms_range),
+            )
+            .with_unit(" ms"),
+            release_ms: nice::FloatParam::new(
+                format!("Release (ms){names_suffix}"),
+                dynamics.release_ms,
+                skewed_range(&eq_ranges.release_ms_range),
+            )
+            .with_unit(" ms"),
         }
     }
 
     pub fn to_eq<F: audio_lib::utils::Float>(&self) -> eq::Eq<F> {
+        let gain_db = F::from(self.gain_db.value()).unwrap();
         eq::Eq {
-            gain: eq::Gain::Db(F::from(self.gain_db.value()).unwrap()),
+            gain: eq::Gain::Db(gain_db),
             frequency: eq::Frequency::LogHz(F::from(self.log_frequency.value()).unwrap()),
-            q: F::from(self.q.value()).unwrap(),
+            // the host parameter is always stored as q, the unit only selects the representation
+            bandwidth: eq::Bandwidth::Q(F::from(self.q.value()).unwrap())
+                .to_unit(self.bandwidth_unit.value().into(), gain_db),
+            eq_type: self.eq_type.value().into(),
+            slope: self.slope.value().into(),
+            alignment: self.alignment.value().into(),
+            design: self.design.value().into(),
+            peak_shape: self.peak_shape.value().into(),
+        }
+    }
+
+    /// Like to_eq, but with the smoothed values, after advancing the smoothers by num_samples
+    pub fn to_smoothed_eq<F: audio_lib::utils::Float>(&self, num_samples: u32) -> eq::Eq<F> {
+        let gain_db = F::from(self.gain_db.smoothed.next_step(num_samples)).unwrap();
+        let log_frequency = F::from(self.log_frequency.smoothed.next_step(num_samples)).unwrap();
+        let q = F::from(self.q.smoothed.next_step(num_samples)).unwrap();
+        eq::Eq {
+            gain: eq::Gain::Db(gain_db),
+            frequency: eq::Frequency::LogHz(log_frequency),
+            bandwidth: eq::Bandwidth::Q(q).to_unit(self.bandwidth_unit.value().into(), gain_db),
             eq_type: self.eq_type.value().into(),
+            slope: self.slope.value().into(),
+            alignment: self.alignment.value().into(),
+            design: self.design.value().into(),
+            peak_shape: self.peak_shape.value().into(),
+        }
+    }
+
+    pub fn to_dynamics<F: audio_lib::utils::Float>(&self) -> dynamic_eq::Dynamics<F> {
+        dynamic_eq::Dynamics {
+            enabled: self.dynamic.value(),
+            sidechain: self.sidechain.value(),
+            threshold_db: F::from(self.threshold_db.value()).unwrap(),
+            ratio: F::from(self.ratio.value()).unwrap(),
+            range_db: F::from(self.range_db.value()).unwrap(),
+            attack_ms: F::from(self.attack_ms.value()).unwrap(),
+            release_ms: F::from(self.release_ms.value()).unwrap(),
         }
     }
 
@@ -95,9 +242,91 @@ impl EqParams {
         setter.end_set_parameter(&self.q);
     }
 
+    pub fn set_bandwidth_unit(
+        &self,
+        bandwidth_unit: eq::BandwidthUnit,
+        setter: &nice::ParamSetter<'_>,
+    ) {
+        setter.begin_set_parameter(&self.bandwidth_unit);
+        setter.set_parameter(&self.bandwidth_unit, bandwidth_unit.into());
+        setter.end_set_parameter(&self.bandwidth_unit);
+    }
+
     pub fn set_eq_type(&self, eq_type: eq::EqType, setter: &nice::ParamSetter<'_>) {
         setter.begin_set_parameter(&self.eq_type);
         setter.set_parameter(&self.eq_type, eq_type.into());
         setter.end_set_parameter(&self.eq_type);
     }
+
+    pub fn set_slope(&self, slope: eq::Slope, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.slope);
+        setter.set_parameter(&self.slope, slope.into());
+        setter.end_set_parameter(&self.slope);
+    }
+
+    pub fn set_alignment(&self, alignment: eq::Alignment, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.alignment);
+        setter.set_parameter(&self.alignment, alignment.into());
+        setter.end_set_parameter(&self.alignment);
+    }
+
+    pub fn set_design(&self, design: eq::Design, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.design);
+        setter.set_parameter(&self.design, design.into());
+        setter.end_set_parameter(&self.design);
+    }
+
+    pub fn set_peak_shape(&self, peak_shape: eq::PeakShape, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.peak_shape);
+        setter.set_parameter(&self.peak_shape, peak_shape.into());
+        setter.end_set_parameter(&self.peak_shape);
+    }
+
+    pub fn set_routing(
+        &self,
+        band_routing: audio_lib::routing::Routing,
+        setter: &nice::ParamSetter<'_>,
+    ) {
+        setter.begin_set_parameter(&self.routing);
+        setter.set_parameter(&self.routing, band_routing.into());
+        setter.end_set_parameter(&self.routing);
+    }
+
+    pub fn set_solo(&self, solo: bool, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.solo);
+        setter.set_parameter(&self.solo, solo);
+        setter.end_set_parameter(&self.solo);
+    }
+
+    /// Sets the params of the dynamics that differ from their current values
+    pub fn set_dynamics<F: utils::Float>(
+        &self,
+        dynamics: &dynamic_eq::Dynamics<F>,
+        setter: &nice::ParamSetter<'_>,
+    ) {
+        for (param, value) in [
+            (&self.dynamic, dynamics.enabled),
+            (&self.sidechain, dynamics.sidechain),
+        ] {
+            if value != param.value() {
+                setter.begin_set_parameter(param);
+                setter.set_parameter(param, value);
+                setter.end_set_parameter(param);
+            }
+        }
+        for (param, value) in [
+            (&self.threshold_db, dynamics.threshold_db),
+            (&self.ratio, dynamics.ratio),
+            (&self.range_db, dynamics.range_db),
+            (&self.attack_ms, dynamics.attack_ms),
+            (&self.release_ms, dynamics.release_ms),
+        ] {
+            let value = value.to_f32().unwrap();
+            if value != param.value() {
+                setter.begin_set_parameter(param);
+                setter.set_parameter(param, value);
+                setter.end_set_parameter(param);
+            }
+        }
+    }
 }
@@ -2,11 +2,20 @@ use crate::*;
 use audio_lib::*;
 use std::sync::{self, atomic};
 
+pub mod alignment;
+pub mod bandwidth_unit;
+pub mod design;
 pub mod eq_params;
 pub mod eq_type;
+pub mod peak_shape;
+pub mod polarity_params;
+pub mod routing;
 pub mod show_params;
+pub mod slope;
+pub mod topology;
 
 pub use eq_params::EqParams;
+pub use polarity_params::PolarityParams;
 pub use show_params::ShowParams;
 
 // hm, can we somehow get rid of this without destroying the nice::Enum and nice::Params derive?
@@ -24,6 +33,21 @@ pub struct PluginParams<
     #[nested(array, group = "eq_params")]
     pub eq_params: [EqParams; NUM_BANDS],
 
+    #[id = "topology"]
+    pub topology: topology::Param,
+
+    #[id = "linear_phase"]
+    pub linear_phase: nice::BoolParam,
+
+    #[id = "output_gain_db"]
+    pub output_gain_db: nice::FloatParam,
+
+    #[nested(array, group = "polarity_params")]
+    pub polarity_params: [PolarityParams; NUM_CHANNELS],
+
+    #[id = "bypass"]
+    pub bypass: nice::BoolParam,
+
     pub sample_rate: nice::AtomicF32,
 
     #[nested(group = "show_params")]
@@ -31,6 +55,19 @@ pub struct PluginParams<
 
     pub analyzer_data:
         fft::signal_analyzer::SharedData<f32, { ANALYZER_NUM_BINS }, { NUM_CHANNELS }>,
+
+    pub sidechain_analyzer_data:
+        fft::signal_analyzer::SharedData<f32, { ANALYZER_NUM_BINS }, { NUM_CHANNELS }>,
+
+    /// From the background task to the processor
+    pub linear_phase_kernel: spsc::swap::Swap<linear_phase::Kernel>,
+
+    /// Current gain changes of the dynamic bands, from the processor to the editor
+    pub dynamic_gains_db: spsc::swap::Swap<[f32; NUM_BANDS]>,
+
+    /// Why bands can't be designed in the processor, which keeps their last valid
+    /// coefficients, from the processor to the editor
+    pub band_errors: spsc::swap::Swap<[Option<biquad::coefficients::Error<f32>>; NUM_BANDS]>,
 }
 
 impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS: usize>
@@ -44,19 +81,106 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
                 EqParams::from_eq(
                     format!(" [{}]", index + 1).as_str(),
                     &settings.init_eqs[index],
-                    &eq_ranges.log_frequency_range,
-                    &eq_ranges.db_range,
-                    &eq_ranges.q_range,
+                    audio_lib::routing::Routing::default(),
+                    &dynamic_eq::Dynamics::default(),
+                    &eq_ranges,
                     smoothing_length_ms,
                 )
             }),
+            topology: topology::Param::new(
+                "Topology",
+                topology::Wrapper::from(biquad::topology::Topology::default()),
+            ),
+            linear_phase: nice::BoolParam::new("Linear Phase", false),
+            output_gain_db: nice::FloatParam::new(
+                "Output Gain (dB)",
+                0_f32,
+                nice::FloatRange::Linear {
+                    min: *eq_ranges.output_gain_db_range.start(),
+                    max: *eq_ranges.output_gain_db_range.end(),
+                },
+            )
+            .with_smoother(nice::SmoothingStyle::Linear(smoothing_length_ms))
+            .with_unit(" dB"),
+            polarity_params: std::array::from_fn(|index| {
+                PolarityParams::new(format!(" [{}]", index + 1).as_str())
+            }),
+            bypass: nice::BoolParam::new("Bypass", false).make_bypass(),
             sample_rate: nice::AtomicF32::new(settings.init_sample_rate),
             show_params: ShowParams::from_options(&settings.ui.init_show_options),
             analyzer_data: fft::signal_analyzer::SharedData::new(settings.init_sample_rate),
+            sidechain_analyzer_data: fft::signal_analyzer::SharedData::new(
+                settings.init_sample_rate,
+            ),
+            linear_phase_kernel: spsc::swap::Swap::from_init_function(
+                &linear_phase::make_passthrough_kernel,
+            ),
+            dynamic_gains_db: spsc::swap::Swap::from_init_value(&[0_f32; NUM_BANDS]),
+            band_errors: spsc::swap::Swap::from_init_value(&[None; NUM_BANDS]),
         }
     }
 
     pub fn eqs<F: utils::Float>(&self) -> [eq::Eq<F>; NUM_BANDS] {
         std::array::from_fn(|index| self.eq_params[index].to_eq())
     }
+
+    /// See EqParams::to_smoothed_eq
+    pub fn smoothed_eqs<F: utils::Float>(&self, num_samples: u32) -> [eq::Eq<F>; NUM_BANDS] {
+        std::array::from_fn(|index| self.eq_params[index].to_smoothed_eq(num_samples))
+    }
+
+    pub fn routings(&self) -> [audio_lib::routing::Routing; NUM_BANDS] {
+        std::array::from_fn(|index| self.eq_params[index].routing.value().into())
+    }
+
+    /// The first soloed band, see Processor::audition
+    pub fn solo_band(&self) -> Option<usize> {
+        self.eq_params
+            .iter()
+            .position(|band_params| band_params.solo.value())
+    }
+
+    pub fn dynamics<F: utils::Float>(&self) -> [dynamic_eq::Dynamics<F>; NUM_BANDS] {
+        std::array::from_fn(|index| self.eq_params[index].to_dynamics())
+    }
+
+    pub fn output<F: utils::Float>(&self) -> egui_lib::Output<F> {
+        egui_lib::Output {
+            gain_db: F::from(self.output_gain_db.value()).unwrap(),
+            invert_polarities: self
+                .polarity_params
+                .iter()
+                .map(|polarity_params| polarity_params.invert.value())
+                .collect(),
+            bypass: self.bypass.value(),
+        }
+    }
+
+    /// Sets the params of the output that differ from their current values
+    pub fn set_output<F: utils::Float>(
+        &self,
+        output: &egui_lib::Output<F>,
+        setter: &nice::ParamSetter<'_>,
+    ) {
+        let gain_db = output.gain_db.to_f32().unwrap();
+        if gain_db != self.output_gain_db.value() {
+            setter.begin_set_parameter(&self.output_gain_db);
+            setter.set_parameter(&self.output_gain_db, gain_db);
+            setter.end_set_parameter(&self.output_gain_db);
+        }
+        for (polarity_params, invert) in self
+            .polarity_params
+            .iter()
+            .zip(output.invert_polarities.iter())
+        {
+            if *invert != polarity_params.invert.value() {
+                polarity_params.set_invert(*invert, setter);
+            }
+        }
+        if output.bypass != self.bypass.value() {
+            setter.begin_set_parameter(&self.bypass);
+            setter.set_parameter(&self.bypass, output.bypass);
+            setter.end_set_parameter(&self.bypass);
+        }
+    }
 }
@@ -0,0 +1,48 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    peak_shape: eq::PeakShape,
+}
+
+impl From<eq::PeakShape> for Wrapper {
+    fn from(peak_shape: eq::PeakShape) -> Self {
+        Self {
+            peak_shape: peak_shape,
+        }
+    }
+}
+
+impl Into<eq::PeakShape> for Wrapper {
+    fn into(self) -> eq::PeakShape {
+        self.peak_shape
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &eq::PeakShape::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.peak_shape as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = eq::PeakShape::try_from(index);
+        match from_result {
+            Ok(peak_shape) => Self {
+                peak_shape: peak_shape,
+            },
+            _ => Self {
+                peak_shape: eq::PeakShape::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
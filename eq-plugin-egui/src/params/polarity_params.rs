@@ -0,0 +1,21 @@
+use super::*;
+
+#[derive(nice::Params)]
+pub struct PolarityParams {
+    #[id = "invert_polarity"]
+    pub invert: nice::BoolParam,
+}
+
+impl PolarityParams {
+    pub fn new(names_suffix: &str) -> Self {
+        Self {
+            invert: nice::BoolParam::new(format!("Invert Polarity{names_suffix}"), false),
+        }
+    }
+
+    pub fn set_invert(&self, invert: bool, setter: &nice::ParamSetter<'_>) {
+        setter.begin_set_parameter(&self.invert);
+        setter.set_parameter(&self.invert, invert);
+        setter.end_set_parameter(&self.invert);
+    }
+}
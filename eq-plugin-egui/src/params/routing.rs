@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    routing: audio_lib::routing::Routing,
+}
+
+impl From<audio_lib::routing::Routing> for Wrapper {
+    fn from(routing: audio_lib::routing::Routing) -> Self {
+        Self { routing: routing }
+    }
+}
+
+impl Into<audio_lib::routing::Routing> for Wrapper {
+    fn into(self) -> audio_lib::routing::Routing {
+        self.routing
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &audio_lib::routing::Routing::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.routing as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = audio_lib::routing::Routing::try_from(index);
+        match from_result {
+            Ok(routing) => Self { routing: routing },
+            _ => Self {
+                routing: audio_lib::routing::Routing::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
@@ -6,10 +6,25 @@ pub struct ShowParams {
     pub gain: atomic::AtomicBool,
     #[persist = "signal_gain_spectrum"]
     pub signal_gain_spectrum: atomic::AtomicBool,
+    #[persist = "sidechain_gain_spectrum"]
+    #[serde(default)]
+    pub sidechain_gain_spectrum: atomic::AtomicBool,
+    #[persist = "analog_gain"]
+    #[serde(default)]
+    pub analog_gain: atomic::AtomicBool,
     #[persist = "phase"]
     pub phase: atomic::AtomicBool,
+    #[persist = "group_delay"]
+    #[serde(default)]
+    pub group_delay: atomic::AtomicBool,
     #[persist = "impulse_response"]
     pub impulse_response: atomic::AtomicBool,
+    #[persist = "step_response"]
+    #[serde(default)]
+    pub step_response: atomic::AtomicBool,
+    #[persist = "cumulative_energy"]
+    #[serde(default)]
+    pub cumulative_energy: atomic::AtomicBool,
     #[persist = "poles_and_zeros"]
     pub poles_and_zeros: atomic::AtomicBool,
 }
@@ -19,8 +34,13 @@ impl ShowParams {
         Self {
             gain: atomic::AtomicBool::new(show_options.gain),
             signal_gain_spectrum: atomic::AtomicBool::new(show_options.signal_gain_spectrum),
+            sidechain_gain_spectrum: atomic::AtomicBool::new(show_options.sidechain_gain_spectrum),
+            analog_gain: atomic::AtomicBool::new(show_options.analog_gain),
             phase: atomic::AtomicBool::new(show_options.phase),
+            group_delay: atomic::AtomicBool::new(show_options.group_delay),
             impulse_response: atomic::AtomicBool::new(show_options.impulse_response),
+            step_response: atomic::AtomicBool::new(show_options.step_response),
+            cumulative_energy: atomic::AtomicBool::new(show_options.cumulative_energy),
             poles_and_zeros: atomic::AtomicBool::new(show_options.poles_and_zeros),
         }
     }
@@ -30,9 +50,19 @@ impl ShowParams {
             .store(options.gain, std::sync::atomic::Ordering::Relaxed);
         self.signal_gain_spectrum
             .store(options.signal_gain_spectrum, atomic::Ordering::Relaxed);
+        self.sidechain_gain_spectrum
+            .store(options.sidechain_gain_spectrum, atomic::Ordering::Relaxed);
+        self.analog_gain
+            .store(options.analog_gain, atomic::Ordering::Relaxed);
         self.phase.store(options.phase, atomic::Ordering::Relaxed);
+        self.group_delay
+            .store(options.group_delay, atomic::Ordering::Relaxed);
         self.impulse_response
             .store(options.impulse_response, atomic::Ordering::Relaxed);
+        self.step_response
+            .store(options.step_response, atomic::Ordering::Relaxed);
+        self.cumulative_energy
+            .store(options.cumulative_energy, atomic::Ordering::Relaxed);
         self.poles_and_zeros
             .store(options.poles_and_zeros, atomic::Ordering::Relaxed);
     }
@@ -41,8 +71,13 @@ impl ShowParams {
         ShowOptions {
             gain: self.gain.load(atomic::Ordering::Relaxed),
             signal_gain_spectrum: self.signal_gain_spectrum.load(atomic::Ordering::Relaxed),
+            sidechain_gain_spectrum: self.sidechain_gain_spectrum.load(atomic::Ordering::Relaxed),
+            analog_gain: self.analog_gain.load(atomic::Ordering::Relaxed),
             phase: self.phase.load(atomic::Ordering::Relaxed),
+            group_delay: self.group_delay.load(atomic::Ordering::Relaxed),
             impulse_response: self.impulse_response.load(atomic::Ordering::Relaxed),
+            step_response: self.step_response.load(atomic::Ordering::Relaxed),
+            cumulative_energy: self.cumulative_energy.load(atomic::Ordering::Relaxed),
             poles_and_zeros: self.poles_and_zeros.load(atomic::Ordering::Relaxed),
         }
     }
@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    slope: eq::Slope,
+}
+
+impl From<eq::Slope> for Wrapper {
+    fn from(slope: eq::Slope) -> Self {
+        Self { slope: slope }
+    }
+}
+
+impl Into<eq::Slope> for Wrapper {
+    fn into(self) -> eq::Slope {
+        self.slope
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &eq::Slope::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.slope as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = eq::Slope::try_from(index);
+        match from_result {
+            Ok(slope) => Self { slope: slope },
+            _ => Self {
+                slope: eq::Slope::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
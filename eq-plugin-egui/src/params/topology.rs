@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wrapper {
+    topology: biquad::topology::Topology,
+}
+
+impl From<biquad::topology::Topology> for Wrapper {
+    fn from(topology: biquad::topology::Topology) -> Self {
+        Self { topology: topology }
+    }
+}
+
+impl Into<biquad::topology::Topology> for Wrapper {
+    fn into(self) -> biquad::topology::Topology {
+        self.topology
+    }
+}
+
+impl nice::Enum for Wrapper {
+    fn variants() -> &'static [&'static str] {
+        &biquad::topology::Topology::ALL_NAMES
+    }
+
+    fn ids() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    fn to_index(self) -> usize {
+        self.topology as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let from_result = biquad::topology::Topology::try_from(index);
+        match from_result {
+            Ok(topology) => Self { topology: topology },
+            _ => Self {
+                topology: biquad::topology::Topology::default(),
+            },
+        }
+    }
+}
+
+pub type Param = nice::EnumParam<Wrapper>;
@@ -7,6 +7,7 @@ pub struct Plugin<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALY
     params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>>,
     processor: processor::Processor<{ NUM_BANDS }, { NUM_CHANNELS }, { ANALYZER_NUM_BINS }>,
     analyzer: analyzer::Analyzer<{ NUM_BANDS }, { NUM_CHANNELS }, { ANALYZER_NUM_BINS }>,
+    output: output::Output<{ NUM_BANDS }, { NUM_CHANNELS }, { ANALYZER_NUM_BINS }>,
     ui_settings: UiSettings,
 }
 
@@ -24,6 +25,7 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
             params: params.clone(),
             processor: processor::Processor::new(params.clone()),
             analyzer: analyzer::Analyzer::new(params.clone(), analyzer_coefficients),
+            output: output::Output::new(params.clone()),
             ui_settings: UiSettings {
                 app: app_settings.ui.clone(),
                 color_palette: color_palette,
@@ -31,6 +33,9 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
         }
     }
 
+    /// The sidechain input of dynamic bands and the analyzer, with the most channels supported
+    const SIDECHAIN_PORTS: [nice::NonZeroU32; 1] = [nice::new_nonzero_u32(NUM_CHANNELS as u32)];
+
     const AUDIO_IO_LAYOUTS_INSTANCE: [nice::AudioIOLayout; NUM_CHANNELS] =
         Self::make_audio_layouts();
 
@@ -43,6 +48,8 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
             let num_channels = (NUM_CHANNELS - i) as u32;
             layouts[i].main_input_channels = nice::NonZeroU32::new(num_channels);
             layouts[i].main_output_channels = nice::NonZeroU32::new(num_channels);
+            layouts[i].aux_input_ports = &Self::SIDECHAIN_PORTS;
+            layouts[i].names.aux_inputs = &["Sidechain"];
             i += 1;
         }
         layouts
@@ -76,33 +83,56 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
     const SAMPLE_ACCURATE_AUTOMATION: bool = true;
 
     type SysExMessage = ();
-    type BackgroundTask = ();
+    type BackgroundTask = processor::Task<NUM_BANDS>;
 
     fn params(&self) -> sync::Arc<dyn nice::Params> {
         self.params.clone()
     }
 
+    fn task_executor(&mut self) -> nice::TaskExecutor<Self> {
+        let params = self.params.clone();
+        Box::new(move |task| match task {
+            processor::Task::DesignLinearPhaseKernel { eqs, sample_rate } => params
+                .linear_phase_kernel
+                .producer
+                .set_and_push(linear_phase::design_kernel(&eqs, sample_rate)),
+        })
+    }
+
     fn initialize(
         &mut self,
         _audio_io_layout: &nice::AudioIOLayout,
         _buffer_config: &nice::BufferConfig,
-        _context: &mut impl nice::InitContext<Self>,
+        context: &mut impl nice::InitContext<Self>,
     ) -> bool {
         let sample_rate = _buffer_config.sample_rate as f32;
         self.params
             .sample_rate
             .store(sample_rate, atomic::Ordering::Relaxed);
-        self.processor.initialize() && self.analyzer.initialize()
+        // every stage allocates its buffers, even if an earlier one failed
+        let processor_initialized = self.processor.initialize();
+        let analyzer_initialized = self.analyzer.initialize();
+        let output_initialized = self
+            .output
+            .initialize(_buffer_config.max_buffer_size as usize);
+        context.set_latency_samples(self.processor.latency());
+        processor_initialized && analyzer_initialized && output_initialized
     }
 
     fn process(
         &mut self,
         buffer: &mut nice::Buffer,
-        _aux: &mut nice::AuxiliaryBuffers,
-        _context: &mut impl nice::ProcessContext<Self>,
+        aux: &mut nice::AuxiliaryBuffers,
+        context: &mut impl nice::ProcessContext<Self>,
     ) -> nice::ProcessStatus {
-        self.processor.process(buffer);
-        self.analyzer.process(buffer);
+        let sidechain = aux.inputs.first();
+        // the dry signal is delayed by the latency of the mode this buffer is processed in
+        self.processor.update_mode(context);
+        self.output
+            .store_dry(buffer, self.processor.latency() as usize);
+        self.processor.process(buffer, sidechain, context);
+        self.output.process(buffer);
+        self.analyzer.process(buffer, sidechain);
         nice::ProcessStatus::Normal
     }
 
@@ -1,10 +1,15 @@
 use crate::*;
 use audio_lib::*;
+use std::ops::Range;
 use std::sync::{self, atomic};
 
-type FilterCoefficients<const NUM_BANDS: usize> =
-    [biquad::coefficients::Coefficients<f32>; NUM_BANDS];
-type Filters<const NUM_BANDS: usize> = [biquad::filter::State<f32>; NUM_BANDS];
+/// Work for the background thread of the plugin, see Plugin::task_executor
+pub enum Task<const NUM_BANDS: usize> {
+    DesignLinearPhaseKernel {
+        eqs: [eq::Eq<f32>; NUM_BANDS],
+        sample_rate: f32,
+    },
+}
 
 pub struct Processor<
     const NUM_BANDS: usize,
@@ -12,9 +17,12 @@ pub struct Processor<
     const ANALYZER_NUM_BINS: usize,
 > {
     plugin_params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>>,
-    eqs: [eq::Eq<f32>; NUM_BANDS],
-    coefficients: FilterCoefficients<NUM_BANDS>,
-    filters: [Filters<NUM_BANDS>; NUM_CHANNELS],
+    chain: EqChain<f32, NUM_CHANNELS>,
+    linear_phase: linear_phase::LinearPhase<NUM_BANDS, NUM_CHANNELS>,
+    is_linear_phase: bool,
+    dynamics: [dynamic_eq::Dynamics<f32>; NUM_BANDS],
+    detectors: [dynamic_eq::Detector<f32>; NUM_BANDS],
+    dynamic_gains_db: [f32; NUM_BANDS],
 }
 
 impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS: usize>
@@ -24,77 +32,280 @@ impl<const NUM_BANDS: usize, const NUM_CHANNELS: usize, const ANALYZER_NUM_BINS:
         plugin_params: sync::Arc<params::PluginParams<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>>,
     ) -> Self {
         Self {
+            chain: EqChain::new([Self::INIT_EQ; NUM_BANDS], Self::INIT_SAMPLE_RATE),
             plugin_params: plugin_params,
-            eqs: [Self::INIT_EQ; NUM_BANDS],
-            coefficients: [Self::INIT_FILTER_COEFFICIENTS; NUM_BANDS],
-            filters: std::array::from_fn(|_| std::array::from_fn(|_| biquad::filter::State::new())),
+            linear_phase: linear_phase::LinearPhase::new(),
+            is_linear_phase: false,
+            dynamics: [dynamic_eq::Dynamics::default(); NUM_BANDS],
+            detectors: std::array::from_fn(|_| dynamic_eq::Detector::new(NUM_CHANNELS)),
+            dynamic_gains_db: [0_f32; NUM_BANDS],
         }
     }
 
+    /// Bands that can't be designed at the sample rate pass the signal through, which doesn't
+    /// fail the initialization, their errors are reported to the editor instead
     pub fn initialize(&mut self) -> bool {
-        for channel_filters in self.filters.iter_mut() {
-            for filter in channel_filters.iter_mut() {
-                filter.reset();
-            }
+        self.chain.set_solo_band(self.plugin_params.solo_band());
+        let mut eqs = self.plugin_params.eqs();
+        Self::audition(&mut eqs, self.chain.solo_band());
+        let sample_rate = self
+            .plugin_params
+            .sample_rate
+            .load(atomic::Ordering::Relaxed);
+        self.linear_phase.initialize(&eqs, sample_rate);
+        self.is_linear_phase = self.plugin_params.linear_phase.value();
+        self.dynamics = self.plugin_params.dynamics();
+        for (detector, dynamics) in self.detectors.iter_mut().zip(self.dynamics.iter()) {
+            detector.set_dynamics(dynamics, sample_rate);
+        }
+        self.reset_dynamics();
+        self.chain
+            .set_topology(self.plugin_params.topology.value().into());
+        let routings = self.plugin_params.routings();
+        for band in 0..NUM_BANDS {
+            self.chain.set_routing(band, routings[band]);
+            self.chain.set_eq(band, &eqs[band]);
         }
-        self.update_coefficients(
-            &self.plugin_params.eqs(),
-            self.plugin_params
-                .sample_rate
-                .load(atomic::Ordering::Relaxed),
-        )
+        // the sample rate might have changed, so all bands are designed anew
+        let _ = self.chain.set_sample_rate(sample_rate);
+        self.push_band_errors();
+        true
     }
 
-    pub fn process(&mut self, buffer: &mut nice::Buffer) {
-        self.update_coefficients(
-            &self.plugin_params.eqs(),
-            self.plugin_params
-                .sample_rate
-                .load(atomic::Ordering::Relaxed),
-        );
+    /// Latency in samples of the current mode, for reporting to the host
+    pub fn latency(&self) -> u32 {
+        if self.is_linear_phase {
+            linear_phase::LATENCY as u32
+        } else {
+            0
+        }
+    }
+
+    /// Switches between linear phase and minimum phase mode, and reports the latency of the
+    /// new mode to the host. Called before process, so that the latency applies to the whole
+    /// buffer.
+    pub fn update_mode(
+        &mut self,
+        context: &mut impl nice::ProcessContext<
+            plugin::Plugin<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>,
+        >,
+    ) {
+        let is_linear_phase = self.plugin_params.linear_phase.value();
+        if is_linear_phase != self.is_linear_phase {
+            self.chain.reset();
+            self.linear_phase.reset();
+            self.reset_dynamics();
+            self.is_linear_phase = is_linear_phase;
+            context.set_latency_samples(self.latency());
+        }
+    }
+
+    /// The coefficients follow the smoothed parameters, updated every SUB_BLOCK_LENGTH
+    /// samples. Together with the buffer splitting at parameter changes of the host (see
+    /// SAMPLE_ACCURATE_AUTOMATION), automation is click-free and accurate to the sub-block.
+    /// In linear phase mode (see update_mode), the kernels follow the parameters instead, see
+    /// process_linear_phase.
+    /// The routing of the bands applies only outside of linear phase mode, where one kernel
+    /// filters all channels. While a band is soloed, it is the only one heard, see audition.
+    pub fn process(
+        &mut self,
+        buffer: &mut nice::Buffer,
+        sidechain: Option<&nice::Buffer>,
+        context: &mut impl nice::ProcessContext<
+            plugin::Plugin<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>,
+        >,
+    ) {
+        let sample_rate = self
+            .plugin_params
+            .sample_rate
+            .load(atomic::Ordering::Relaxed);
+
+        self.chain.set_solo_band(self.plugin_params.solo_band());
+
+        if self.is_linear_phase {
+            self.process_linear_phase(buffer, sample_rate, context);
+            return;
+        }
+
+        self.chain
+            .set_topology(self.plugin_params.topology.value().into());
+        self.update_routings();
 
         assert!(buffer.channels() <= NUM_CHANNELS);
+        let num_samples = buffer.samples();
         let buffer_slice = buffer.as_slice();
-        for channel in 0..buffer_slice.len() {
-            let channel_samples = buffer_slice.get_mut(channel).unwrap();
-            let channel_filters = &mut self.filters[channel];
-            for sample in (*channel_samples).iter_mut() {
-                let mut processing_sample = *sample;
-                for i in 0..NUM_BANDS {
-                    processing_sample =
-                        channel_filters[i].process(&self.coefficients[i], processing_sample);
-                }
-                *sample = processing_sample;
+        let sidechain_slice = sidechain.map(|sidechain| sidechain.as_slice_immutable());
+        let mut sub_block_start = 0;
+        while sub_block_start < num_samples {
+            let sub_block_length = Self::SUB_BLOCK_LENGTH.min(num_samples - sub_block_start);
+            let sub_block = sub_block_start..sub_block_start + sub_block_length;
+            let mut eqs = self.plugin_params.smoothed_eqs(sub_block_length as u32);
+            self.apply_dynamics(
+                &mut eqs,
+                buffer_slice,
+                sidechain_slice,
+                sub_block.clone(),
+                sample_rate,
+            );
+            Self::audition(&mut eqs, self.chain.solo_band());
+            for (band, eq) in eqs.iter().enumerate() {
+                self.chain.set_eq(band, eq);
             }
+            self.chain.process_block(buffer_slice, sub_block);
+            sub_block_start += sub_block_length;
         }
+        self.plugin_params
+            .dynamic_gains_db
+            .producer
+            .set_and_push(self.dynamic_gains_db);
+        self.push_band_errors();
     }
 
-    fn update_coefficients(&mut self, new_eqs: &[eq::Eq<f32>], sample_rate: f32) -> bool {
-        assert!(new_eqs.len() >= NUM_BANDS);
-        let mut success = true;
-        for i in 0..NUM_BANDS {
-            let eq = &mut self.eqs[i];
-            let new_eq = &new_eqs[i];
-            if *new_eq != *eq {
-                let new_coefficients =
-                    biquad::coefficients::Coefficients::from_eq(new_eq, sample_rate);
-                if !biquad::utils::is_stable(&new_coefficients) {
-                    success = false;
+    /// Replaces the soloed band with a band-pass at its frequency and bandwidth, so that
+    /// only the part of the signal it affects is heard, and bypasses the others
+    fn audition(eqs: &mut [eq::Eq<f32>; NUM_BANDS], solo_band: Option<usize>) {
+        if let Some(solo_band) = solo_band {
+            for (band, eq) in eqs.iter_mut().enumerate() {
+                if band == solo_band {
+                    *eq = eq.to_band_pass();
                 } else {
-                    *eq = new_eq.clone();
-                    self.coefficients[i] = new_coefficients;
+                    eq.eq_type = eq::EqType::Bypassed;
                 }
             }
         }
-        success
     }
 
-    const INIT_FILTER_COEFFICIENTS: biquad::coefficients::Coefficients<f32> =
-        biquad::coefficients::Coefficients::muted();
+    /// Bands that process other channels than before start from fresh states
+    fn update_routings(&mut self) {
+        let routings = self.plugin_params.routings();
+        for band in 0..NUM_BANDS {
+            if routings[band] != self.chain.routing(band) {
+                self.chain.set_routing(band, routings[band]);
+                self.detectors[band].reset();
+            }
+        }
+    }
+
+    /// The detectors of the dynamic bands listen to the input of the sub-block, or the
+    /// sidechain if the band is keyed from it, then the gains of the bands are moved by the
+    /// change for the level at its end. Dynamics don't apply in linear phase mode, where the
+    /// kernels can't follow the signal.
+    fn apply_dynamics(
+        &mut self,
+        eqs: &mut [eq::Eq<f32>; NUM_BANDS],
+        channels: &[&mut [f32]],
+        sidechain_channels: Option<&[&mut [f32]]>,
+        sub_block: Range<usize>,
+        sample_rate: f32,
+    ) {
+        let dynamics = self.plugin_params.dynamics();
+        let mut frame = [0_f32; NUM_CHANNELS];
+        for (band, eq) in eqs.iter_mut().enumerate() {
+            let detector = &mut self.detectors[band];
+            if !dynamics[band].is_active(eq) {
+                if self.dynamic_gains_db[band] != 0_f32 {
+                    // the level is stale once the band is dynamic again
+                    detector.reset();
+                    self.dynamic_gains_db[band] = 0_f32;
+                }
+                continue;
+            }
+            if dynamics[band] != self.dynamics[band] {
+                if dynamics[band].sidechain != self.dynamics[band].sidechain {
+                    // the level of the other input is stale
+                    detector.reset();
+                }
+                detector.set_dynamics(&dynamics[band], sample_rate);
+                self.dynamics[band] = dynamics[band];
+            }
+            detector.set_band(eq, sample_rate);
+            let detector_channels = match sidechain_channels {
+                Some(sidechain_channels) if dynamics[band].sidechain => sidechain_channels,
+                _ => channels,
+            };
+            assert!(detector_channels.len() <= NUM_CHANNELS);
+            // the detector listens to the channels the band processes
+            let band_routing = self.chain.routing(band);
+            let routed_channels = band_routing.channels(detector_channels.len());
+            for index in sub_block.clone() {
+                for (value, samples) in frame.iter_mut().zip(detector_channels) {
+                    *value = samples[index];
+                }
+                if band_routing.encoding() == Some(routing::Encoding::MidSide)
+                    && detector_channels.len() > 1
+                {
+                    (frame[0], frame[1]) = routing::encode_mid_side(frame[0], frame[1]);
+                }
+                detector.process(&frame[routed_channels.clone()]);
+            }
+            self.dynamic_gains_db[band] = dynamics[band].gain_change_db(detector.level_db());
+            *eq = dynamics[band].apply(eq, self.dynamic_gains_db[band]);
+        }
+    }
+
+    fn reset_dynamics(&mut self) {
+        for detector in self.detectors.iter_mut() {
+            detector.reset();
+        }
+        self.dynamic_gains_db = [0_f32; NUM_BANDS];
+        self.plugin_params
+            .dynamic_gains_db
+            .producer
+            .set_and_push(self.dynamic_gains_db);
+    }
+
+    /// Band settings changes request a new kernel from the background task, which is
+    /// crossfaded in once it is ready. The smoothers keep running, so that they don't sweep
+    /// from outdated values when switching back.
+    fn process_linear_phase(
+        &mut self,
+        buffer: &mut nice::Buffer,
+        sample_rate: f32,
+        context: &mut impl nice::ProcessContext<
+            plugin::Plugin<NUM_BANDS, NUM_CHANNELS, ANALYZER_NUM_BINS>,
+        >,
+    ) {
+        let num_samples = buffer.samples();
+        self.plugin_params.smoothed_eqs::<f32>(num_samples as u32);
+
+        let mut eqs = self.plugin_params.eqs();
+        Self::audition(&mut eqs, self.chain.solo_band());
+        if let Some(eqs) = self.linear_phase.request_kernel(&eqs, sample_rate) {
+            context.execute_background(Task::DesignLinearPhaseKernel { eqs, sample_rate });
+        }
+        self.linear_phase
+            .pull_kernel(&self.plugin_params.linear_phase_kernel.consumer);
+
+        assert!(buffer.channels() <= NUM_CHANNELS);
+        for (channel, samples) in buffer.as_slice().iter_mut().enumerate() {
+            self.linear_phase.process(channel, samples);
+        }
+    }
+
+    /// Bands with invalid parameters keep their previous coefficients until they can be
+    /// designed again, see EqChain::set_eq, their errors are shown in the editor
+    fn push_band_errors(&mut self) {
+        let band_errors =
+            std::array::from_fn(|band| self.chain.error(band).map(|error| error.cast()));
+        self.plugin_params
+            .band_errors
+            .producer
+            .set_and_push(band_errors);
+    }
+
+    /// Short enough for smooth sweeps, long enough to keep the cost of the designs low
+    const SUB_BLOCK_LENGTH: usize = 32;
+
     const INIT_EQ: eq::Eq<f32> = eq::Eq {
         gain: eq::Gain::Amplitude(0_f32),
         frequency: eq::Frequency::Hz(0_f32),
-        q: 0.0,
+        bandwidth: eq::Bandwidth::Q(0.0),
         eq_type: eq::EqType::Volume,
+        slope: eq::Slope::Db12,
+        alignment: eq::Alignment::Butterworth,
+        design: eq::Design::Cookbook,
+        peak_shape: eq::PeakShape::Cookbook,
     };
+    /// Replaced in initialize
+    const INIT_SAMPLE_RATE: f32 = 48000_f32;
 }